@@ -0,0 +1,60 @@
+//! The full setup, prove, and verify flow over BLS12-381, timing each step.
+//!
+//! Proves knowledge of `x, y, z, u` with `x * y * z * u = out` for a public `out`, using the R1CS from the README.
+//! Run with `cargo run --release --example bls12_381`.
+use ark_bls12_381::{Bls12_381, Fr};
+use groth16::circuits::{QAP, R1CS};
+use groth16::groth16::TrustedSetupOutput;
+use rootcause::Report;
+use std::time::Instant;
+
+fn main() -> Result<(), Report> {
+    // Wires are 1, out, x, y, z, u, x * y, z * u
+    let l = vec![
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![1, 0, 0],
+        vec![0, 0, 0],
+        vec![0, 1, 0],
+        vec![0, 0, 0],
+        vec![0, 0, 1],
+        vec![0, 0, 0],
+    ];
+    let r = vec![
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![1, 0, 0],
+        vec![0, 0, 0],
+        vec![0, 1, 0],
+        vec![0, 0, 0],
+        vec![0, 0, 1],
+    ];
+    let o = vec![
+        vec![0, 0, 0],
+        vec![0, 0, 1],
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![0, 0, 0],
+        vec![1, 0, 0],
+        vec![0, 1, 0],
+    ];
+    let r1cs: R1CS<Fr> = R1CS::new(l, r, o, vec![1, 120]);
+    let witness: Vec<Fr> = [1, 120, 2, 3, 4, 5, 6, 20].map(Fr::from).to_vec();
+
+    let start = Instant::now();
+    let trusted_setup: TrustedSetupOutput<Bls12_381> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+    println!("Setup took {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let proof = trusted_setup.prove(&witness)?;
+    println!("Proving took {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let valid = proof.verify(&trusted_setup, &[Fr::from(1), Fr::from(120)])?;
+    println!("Verifying took {:?}", start.elapsed());
+
+    println!("Proof is {}", if valid { "valid" } else { "invalid" });
+    Ok(())
+}
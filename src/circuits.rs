@@ -1,26 +1,55 @@
+use crate::error::Groth16Error;
 use crate::helpers::ark_de;
 use crate::helpers::ark_se;
+use crate::helpers::rand_scalar;
+use crate::helpers::{from_bytes, to_bytes};
 use crate::polynomial::Polynomial;
-use ark_ff::FftField;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use ark_ff::{BigInteger, FftField, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use core::iter::zip;
+#[cfg(any(feature = "std", test))]
 use log::debug;
-use rand::Rng;
-use rootcause::{Report, report};
+use rootcause::prelude::ResultExt;
+use rootcause::{Report, bail, report};
 use serde::{Deserialize, Serialize};
-use std::iter::zip;
+
+/// Exporters for writing constraint systems in formats other tools can read. Currently only circom's iden3 `.r1cs`.
+#[cfg(feature = "std")]
+pub mod export;
+
+mod builder;
+pub use builder::Builder;
+
+/// Conversion from arkworks constraint systems, so circuits written with `ark-relations` can be proven here.
+#[cfg(feature = "ark-relations")]
+pub mod arkworks;
 
 /// Represents a Rank 1 Constraint System. Should be created using `R1CS::new(...)`,
 /// which lets you provide matrices with any type that can be converted into the Scalar type.
 /// (E.g. to allow vec literals)
-#[derive(Clone, Debug)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, CanonicalSerialize, CanonicalDeserialize,
+)]
 pub struct R1CS<S: FftField> {
     /// Column-wise, i.e. a vec of columns
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     pub L: Vec<Vec<S>>,
     /// Column-wise, i.e. a vec of columns
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     pub R: Vec<Vec<S>>,
     /// Column-wise, i.e. a vec of columns
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     pub O: Vec<Vec<S>>,
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     pub public_witness: Vec<S>,
+    /// Optional human readable label for each constraint (row), e.g. pointing back to the source of a DSL.
+    /// Empty if the circuit is unlabelled.
+    #[serde(default)]
+    pub constraint_labels: Vec<String>,
 }
 
 impl<S: FftField> R1CS<S> {
@@ -42,22 +71,298 @@ impl<S: FftField> R1CS<S> {
                 .map(|column| column.iter().map(|x| S::from(*x)).collect())
                 .collect(),
             public_witness: public_witness.iter().map(|x| S::from(*x)).collect(),
+            constraint_labels: Vec::new(),
+        }
+    }
+
+    /// Check a witness where some entries are still unknown (`None`), e.g. while filling it in incrementally.
+    /// Constraints touching only known wires are reported as `Satisfied` or `Violated`, the rest as `Undetermined`.
+    /// A witness shorter than the number of wires is treated as having the missing entries unknown.
+    pub fn verify_partial(&self, witness: &[Option<S>]) -> PartialVerifyReport {
+        let constraints = (0..self.num_constraints())
+            .map(|row| {
+                let dot = |matrix: &Vec<Vec<S>>| -> Option<S> {
+                    matrix
+                        .iter()
+                        .enumerate()
+                        .map(|(i, column)| {
+                            let coefficient = *column.get(row).unwrap_or(&S::default());
+                            if coefficient == S::default() {
+                                return Some(S::default());
+                            }
+                            witness.get(i).copied().flatten().map(|w| coefficient * w)
+                        })
+                        .sum()
+                };
+
+                match (dot(&self.L), dot(&self.R), dot(&self.O)) {
+                    (Some(l), Some(r), Some(o)) if l * r == o => ConstraintStatus::Satisfied,
+                    (Some(_), Some(_), Some(_)) => ConstraintStatus::Violated,
+                    _ => ConstraintStatus::Undetermined,
+                }
+            })
+            .collect();
+
+        PartialVerifyReport { constraints }
+    }
+
+    /// Fill in the unknown (`None`) wires of a witness from the known ones, e.g. the intermediate products of a
+    /// circuit from its inputs. A witness shorter than the number of wires is treated as having the missing
+    /// entries unknown.
+    ///
+    /// Repeatedly looks for a constraint with exactly one unknown wire, appearing in only one of `L`, `R`, and `O`,
+    /// and solves the constraint for it, until every wire is known. This handles feed-forward circuits, such as
+    /// multiplication gates whose output is only used by later gates, in any order.
+    /// Fails with `Groth16Error::UnsolvedWire` if a wire can't be determined this way, or
+    /// `Groth16Error::UnsatisfiedConstraint` if the known wires are inconsistent with the constraints.
+    pub fn solve(&self, inputs: &[Option<S>]) -> Result<Vec<S>, Report> {
+        let mut witness: Vec<Option<S>> = (0..self.L.len())
+            .map(|i| inputs.get(i).copied().flatten())
+            .collect();
+
+        let mut progress = true;
+        while progress {
+            progress = false;
+            for row in 0..self.num_constraints() {
+                if let Some((wire, value)) = self.solve_constraint(row, &witness) {
+                    witness[wire] = Some(value);
+                    progress = true;
+                }
+            }
+        }
+
+        let witness = witness
+            .into_iter()
+            .enumerate()
+            .map(|(wire, value)| value.ok_or(report!(Groth16Error::UnsolvedWire { wire })))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Solving for witness")?;
+        self.verify_detailed(&witness)
+            .context("Checking solved witness")?;
+        Ok(witness)
+    }
+
+    /// Solve constraint `row` for its single unknown wire, if it has exactly one and it appears linearly.
+    fn solve_constraint(&self, row: usize, witness: &[Option<S>]) -> Option<(usize, S)> {
+        let coefficient = |matrix: &Vec<Vec<S>>, wire: usize| -> S {
+            *matrix[wire].get(row).unwrap_or(&S::default())
+        };
+        let unknown = |matrix: &Vec<Vec<S>>| -> Vec<usize> {
+            (0..matrix.len())
+                .filter(|&wire| {
+                    matches!(witness.get(wire), Some(None))
+                        && coefficient(matrix, wire) != S::default()
+                })
+                .collect()
+        };
+        // The dot product of a row with the known wires
+        let known = |matrix: &Vec<Vec<S>>| -> S {
+            (0..matrix.len())
+                .filter_map(|wire| {
+                    let w = witness.get(wire).copied().flatten()?;
+                    Some(coefficient(matrix, wire) * w)
+                })
+                .sum()
+        };
+
+        let (l_unknown, r_unknown, o_unknown) =
+            (unknown(&self.L), unknown(&self.R), unknown(&self.O));
+        let (l, r, o) = (known(&self.L), known(&self.R), known(&self.O));
+        match (
+            l_unknown.as_slice(),
+            r_unknown.as_slice(),
+            o_unknown.as_slice(),
+        ) {
+            // l * r = o + c * w
+            ([], [], [wire]) => Some((*wire, (l * r - o) / coefficient(&self.O, *wire))),
+            // (l + c * w) * r = o
+            ([wire], [], []) if r != S::default() => {
+                Some((*wire, (o / r - l) / coefficient(&self.L, *wire)))
+            }
+            // l * (r + c * w) = o
+            ([], [wire], []) if l != S::default() => {
+                Some((*wire, (o / l - r) / coefficient(&self.R, *wire)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fail with `Groth16Error::PublicWitnessTooLong` unless every public witness element has a wire.
+    fn check_public_witness_fits(&self) -> Result<(), Report> {
+        if self.public_witness.len() > self.L.len() {
+            return Err(report!(Groth16Error::PublicWitnessTooLong {
+                public: self.public_witness.len(),
+                wires: self.L.len(),
+            })
+            .context("Checking public witness fits in the circuit")
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Check `L`, `R`, and `O` all have the same number of columns, every column has the same number of rows, and
+    /// there's at least one constraint.
+    fn check_shape(&self) -> Result<(), Report> {
+        let num_constraints = self.num_constraints();
+        if num_constraints == 0 {
+            return Err(report!(Groth16Error::EmptyCircuit)
+                .attach("R1CS has no constraints")
+                .into());
         }
+        if self.L.len() != self.R.len() || self.L.len() != self.O.len() {
+            return Err(report!(Groth16Error::InconsistentMatrices)
+                .attach(format!(
+                    "Columns: L {}, R {}, O {}",
+                    self.L.len(),
+                    self.R.len(),
+                    self.O.len()
+                ))
+                .into());
+        }
+        for (name, matrix) in [("L", &self.L), ("R", &self.R), ("O", &self.O)] {
+            if let Some(column) = matrix.iter().position(|x| x.len() != num_constraints) {
+                return Err(report!(Groth16Error::InconsistentMatrices)
+                    .attach(format!(
+                        "Column {column} of {name} has {} rows, expected {num_constraints}",
+                        matrix[column].len()
+                    ))
+                    .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Label each constraint (row), so verification errors can name the constraint that failed.
+    pub fn with_constraint_labels(mut self, labels: Vec<String>) -> Self {
+        self.constraint_labels = labels;
+        self
+    }
+
+    /// The number of constraints (rows) in the system, i.e. the height of the tallest column of any matrix, or 0 if
+    /// there are no columns. This isn't validated: for ragged matrices no column need actually have this height, which
+    /// `QAP::try_from_r1cs` rejects with `Groth16Error::InconsistentMatrices`.
+    pub fn num_constraints(&self) -> usize {
+        self.L
+            .iter()
+            .chain(&self.R)
+            .chain(&self.O)
+            .map(|column| column.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The number of variables (wires, or columns), i.e. the length of a witness. If the matrices have different
+    /// numbers of columns this is the largest, which `QAP::try_from_r1cs` rejects.
+    pub fn num_variables(&self) -> usize {
+        self.L.len().max(self.R.len()).max(self.O.len())
+    }
+
+    /// Compose two circuits, giving a circuit with `self`'s constraints followed by `other`'s.
+    ///
+    /// Column `i` of `other` becomes column `wire_map[i]` of the result, so wires shared between the circuits (e.g. the
+    /// constant 1 in wire 0) are identified by mapping them onto `self`'s wires, and mapping onto indices past the end
+    /// of `self` adds new wires, with any columns left over in between all zero. The result keeps `self`'s
+    /// public witness, so `other`'s public wires should be mapped onto `self`'s or treated as private.
+    /// Labels are kept if either circuit has them, with any unlabelled constraints labelled `""`.
+    ///
+    /// Fails with `Groth16Error::WireMapLength` unless `wire_map` has an entry for each of `other`'s wires.
+    pub fn merge(&self, other: &R1CS<S>, wire_map: &[usize]) -> Result<R1CS<S>, Report> {
+        if wire_map.len() != other.num_variables() {
+            bail!(Groth16Error::WireMapLength {
+                expected: other.num_variables(),
+                got: wire_map.len()
+            });
+        }
+
+        let self_rows = self.num_constraints();
+        let rows = self_rows + other.num_constraints();
+        let wires = wire_map
+            .iter()
+            .map(|wire| wire + 1)
+            .chain([self.num_variables()])
+            .max()
+            .unwrap_or(0);
+        let combine = |a: &Vec<Vec<S>>, b: &Vec<Vec<S>>| -> Vec<Vec<S>> {
+            let mut merged = vec![vec![S::default(); rows]; wires];
+            for (column, values) in a.iter().enumerate() {
+                merged[column][..values.len()].copy_from_slice(values);
+            }
+            for (column, values) in zip(wire_map, b) {
+                for (row, value) in values.iter().enumerate() {
+                    merged[*column][self_rows + row] += *value;
+                }
+            }
+            merged
+        };
+
+        let constraint_labels =
+            if self.constraint_labels.is_empty() && other.constraint_labels.is_empty() {
+                Vec::new()
+            } else {
+                let labels = |r1cs: &R1CS<S>| {
+                    (0..r1cs.num_constraints())
+                        .map(|row| r1cs.constraint_labels.get(row).cloned().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                };
+                [labels(self), labels(other)].concat()
+            };
+
+        Ok(R1CS {
+            L: combine(&self.L, &other.L),
+            R: combine(&self.R, &other.R),
+            O: combine(&self.O, &other.O),
+            public_witness: self.public_witness.clone(),
+            constraint_labels,
+        })
+    }
+
+    /// Check the witness satisfies every constraint.
+    /// Unlike `verify`, fails with `Groth16Error::UnsatisfiedConstraint` naming the first violated constraint,
+    /// by its label if the circuit has them.
+    pub fn verify_detailed(&self, witness: &[S]) -> Result<(), Report> {
+        if witness.is_empty() {
+            bail!(Groth16Error::EmptyWitness);
+        }
+        if witness.len() != self.L.len() {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.L.len(),
+                got: witness.len()
+            });
+        }
+
+        for row in 0..self.num_constraints() {
+            let dot = |matrix: &Vec<Vec<S>>| -> S {
+                zip(matrix, witness)
+                    .map(|(column, w)| *column.get(row).unwrap_or(&S::default()) * *w)
+                    .sum()
+            };
+            if dot(&self.L) * dot(&self.R) != dot(&self.O) {
+                bail!(unsatisfied_constraint(&self.constraint_labels, row));
+            }
+        }
+        Ok(())
     }
 
-    pub(crate) fn verify(&self, witness: &Vec<S>) -> Result<bool, Report> {
+    #[cfg(any(feature = "std", test))]
+    pub(crate) fn verify(&self, witness: &[S]) -> Result<bool, Report> {
+        if self.L.is_empty() {
+            bail!(Groth16Error::NoWires);
+        }
+        if witness.is_empty() {
+            bail!(Groth16Error::EmptyWitness);
+        }
         let o = zip(&self.O, witness)
             .map(|(o, w)| o.iter().map(|x| *x * *w).collect::<Vec<_>>())
             .reduce(|a, b| zip(a, b).map(|(a_i, b_i)| a_i + b_i).collect())
-            .ok_or(report!("Empty vec"))?;
+            .ok_or(report!(Groth16Error::EmptyWitness))?;
         let l = zip(&self.L, witness)
             .map(|(o, w)| o.iter().map(|x| *x * *w).collect::<Vec<_>>())
             .reduce(|a, b| zip(a, b).map(|(a_i, b_i)| a_i + b_i).collect())
-            .ok_or(report!("Empty vec"))?;
+            .ok_or(report!(Groth16Error::EmptyWitness))?;
         let r = zip(&self.R, witness)
             .map(|(o, w)| o.iter().map(|x| *x * *w).collect::<Vec<_>>())
             .reduce(|a, b| zip(a, b).map(|(a_i, b_i)| a_i + b_i).collect())
-            .ok_or(report!("Empty vec"))?;
+            .ok_or(report!(Groth16Error::EmptyWitness))?;
 
         debug!("{:?} == {:?} * {:?}", o, l, r);
         let rhs = zip(l, r).map(|(a_i, b_i)| a_i * b_i).collect::<Vec<_>>();
@@ -66,6 +371,205 @@ impl<S: FftField> R1CS<S> {
     }
 }
 
+impl<S: PrimeField> R1CS<S> {
+    /// Check the witness satisfies every constraint, doing the arithmetic in another prime field `T`, e.g. a small
+    /// one for quick sanity checks. Each coefficient is carried over as the integer it represents, taking elements
+    /// above half the modulus as negative so that coefficients like `-1` survive.
+    ///
+    /// This is only a smoke test: the two fields can disagree once values wrap around the smaller modulus,
+    /// so a result here says nothing definitive about satisfaction over `S`.
+    pub fn verify_over<T: PrimeField>(&self, witness: &[T]) -> Result<bool, Report> {
+        if witness.is_empty() {
+            bail!(Groth16Error::EmptyWitness);
+        }
+        if witness.len() != self.L.len() {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.L.len(),
+                got: witness.len()
+            });
+        }
+
+        Ok((0..self.num_constraints()).all(|row| {
+            let dot = |matrix: &Vec<Vec<S>>| -> T {
+                zip(matrix, witness)
+                    .map(|(column, w)| change_field::<S, T>(column.get(row).copied()) * *w)
+                    .sum()
+            };
+            dot(&self.L) * dot(&self.R) == dot(&self.O)
+        }))
+    }
+}
+
+/// The centered integer representative of `x` in `S`, reduced into `T`. `None` maps to zero.
+fn change_field<S: PrimeField, T: PrimeField>(x: Option<S>) -> T {
+    let Some(x) = x else {
+        return T::ZERO;
+    };
+    let reduce = |x: S| T::from_le_bytes_mod_order(&x.into_bigint().to_bytes_le());
+    if x.into_bigint() <= S::MODULUS_MINUS_ONE_DIV_TWO {
+        reduce(x)
+    } else {
+        -reduce(-x)
+    }
+}
+
+/// Check the field has at least `points` distinct elements to use as an evaluation domain, i.e. its characteristic is
+/// more than `points`. Otherwise the default domain `1, 2, ..., n` wraps around and collides, as would any other.
+/// Fails with `Groth16Error::InvalidDomain` if it doesn't.
+pub(crate) fn check_domain_size<S: FftField>(points: usize) -> Result<(), Report> {
+    let characteristic = S::characteristic();
+    // A characteristic with any higher limbs set is at least 2^64, so more than any usize
+    if characteristic.iter().skip(1).all(|limb| *limb == 0) && characteristic[0] <= points as u64 {
+        return Err(report!(Groth16Error::InvalidDomain {
+            needed: points,
+            have: characteristic[0] as usize
+        })
+        .attach(format!(
+            "Field characteristic {} is too small for {points} distinct domain points, they would wrap around and \
+             collide",
+            characteristic[0]
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// How a single constraint fared in `R1CS::verify_partial`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintStatus {
+    /// Every wire the constraint touches is known, and it holds
+    Satisfied,
+    /// Every wire the constraint touches is known, and it doesn't hold
+    Violated,
+    /// The constraint touches at least one unknown wire
+    Undetermined,
+}
+
+/// The status of each constraint (row) of an R1CS for a partially known witness, from `R1CS::verify_partial`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialVerifyReport {
+    pub constraints: Vec<ConstraintStatus>,
+}
+
+impl PartialVerifyReport {
+    /// Indices of the constraints with the given status.
+    pub fn with_status(&self, status: ConstraintStatus) -> Vec<usize> {
+        self.constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == status)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Whether every constraint is known to be satisfied, i.e. the witness is complete and valid.
+    pub fn is_satisfied(&self) -> bool {
+        self.constraints
+            .iter()
+            .all(|s| *s == ConstraintStatus::Satisfied)
+    }
+}
+
+/// A Rank 1 Constraint System stored sparsely, with each column as a list of its non-zero `(row, value)` entries.
+/// Real circuits only touch a handful of wires per constraint, so this avoids storing every zero.
+/// Convert to and from the dense `R1CS` with `From`, or go straight to a `QAP` with `QAP::from`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseR1CS<S: FftField> {
+    /// Column-wise non-zero `(row, value)` entries of the left matrix
+    pub l: Vec<Vec<(usize, S)>>,
+    /// Column-wise non-zero `(row, value)` entries of the right matrix
+    pub r: Vec<Vec<(usize, S)>>,
+    /// Column-wise non-zero `(row, value)` entries of the output matrix
+    pub o: Vec<Vec<(usize, S)>>,
+    /// Number of constraints (rows), which can't be recovered from the entries if trailing rows are all zero
+    pub num_constraints: usize,
+    pub public_witness: Vec<S>,
+    /// Optional human readable label for each constraint (row). Empty if the circuit is unlabelled.
+    pub constraint_labels: Vec<String>,
+}
+
+impl<S: FftField> SparseR1CS<S> {
+    /// Check the witness satisfies every constraint, only visiting the non-zero entries.
+    pub fn verify(&self, witness: &[S]) -> Result<bool, Report> {
+        if witness.is_empty() {
+            bail!(Groth16Error::EmptyWitness);
+        }
+        if witness.len() != self.l.len() {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.l.len(),
+                got: witness.len()
+            });
+        }
+
+        let rows = |matrix: &Vec<Vec<(usize, S)>>| -> Vec<S> {
+            let mut out = vec![S::default(); self.num_constraints];
+            for (column, w) in zip(matrix, witness) {
+                for (row, value) in column {
+                    out[*row] += *value * *w;
+                }
+            }
+            out
+        };
+
+        let rhs = zip(rows(&self.l), rows(&self.r))
+            .map(|(a_i, b_i)| a_i * b_i)
+            .collect::<Vec<_>>();
+        Ok(rows(&self.o) == rhs)
+    }
+}
+
+impl<S: FftField> From<R1CS<S>> for SparseR1CS<S> {
+    fn from(r1cs: R1CS<S>) -> Self {
+        let sparsify = |matrix: &Vec<Vec<S>>| -> Vec<Vec<(usize, S)>> {
+            matrix
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, value)| **value != S::default())
+                        .map(|(row, value)| (row, *value))
+                        .collect()
+                })
+                .collect()
+        };
+
+        SparseR1CS {
+            l: sparsify(&r1cs.L),
+            r: sparsify(&r1cs.R),
+            o: sparsify(&r1cs.O),
+            num_constraints: r1cs.num_constraints(),
+            public_witness: r1cs.public_witness,
+            constraint_labels: r1cs.constraint_labels,
+        }
+    }
+}
+
+impl<S: FftField> From<SparseR1CS<S>> for R1CS<S> {
+    fn from(sparse: SparseR1CS<S>) -> Self {
+        let densify = |matrix: &Vec<Vec<(usize, S)>>| -> Vec<Vec<S>> {
+            matrix
+                .iter()
+                .map(|column| {
+                    let mut dense = vec![S::default(); sparse.num_constraints];
+                    for (row, value) in column {
+                        dense[*row] = *value;
+                    }
+                    dense
+                })
+                .collect()
+        };
+
+        R1CS {
+            L: densify(&sparse.l),
+            R: densify(&sparse.r),
+            O: densify(&sparse.o),
+            public_witness: sparse.public_witness,
+            constraint_labels: sparse.constraint_labels,
+        }
+    }
+}
+
 /// Represents a Quadratic Arithmetic Program. Cannot be instantiated directly, should instead be derived from a Rank 1 Constraint System using `QAP::from(r1cs)`
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, CanonicalDeserialize, CanonicalSerialize,
@@ -85,45 +589,336 @@ where
     pub v: Vec<Polynomial<S>>,
     #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     pub public_witness: Vec<S>,
+    /// Labels carried over from the R1CS, one per constraint. Empty if the circuit is unlabelled.
+    #[serde(default)]
+    pub constraint_labels: Vec<String>,
+    /// The evaluation points the polynomials were interpolated over, constraint `i` sits at `domain[i]`.
+    /// `1, 2, ..., n` by default, or a multiplicative subgroup when created with `QAP::from_over_domain`.
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    pub domain: Vec<S>,
 }
 
 impl<S: FftField> QAP<S> {
-    /// A QAP has degree `n` where `n` is the number of rows in the R1CS it was formed from
+    /// Serialize in arkworks' compressed canonical encoding, which is much smaller than the JSON form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(self)
+    }
+
+    /// Inverse of `to_bytes`. Fails with `Groth16Error::InvalidEncoding` if the bytes don't decode.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Report> {
+        from_bytes(bytes, "Decoding QAP")
+    }
+
+    /// A QAP has degree `n` where `n` is the number of rows in the R1CS it was formed from, i.e. the number of points
+    /// in its domain. This is also the degree of the vanishing polynomial `t(x)`, and what the trusted setup is sized by.
     pub fn degree(&self) -> usize {
-        self.max_polynomial_degree() + 1
+        self.domain.len()
     }
 
+    /// The highest degree of any of the `u`, `v`, and `w` polynomials, ignoring trailing zero coefficients, which
+    /// arithmetic other than `Mul` or padded serialized data can leave behind. At most `degree() - 1`.
     pub fn max_polynomial_degree(&self) -> usize {
-        vec![
-            self.u.iter().map(|x| x.degree()).max().unwrap_or(0),
-            self.v.iter().map(|x| x.degree()).max().unwrap_or(0),
-            self.w.iter().map(|x| x.degree()).max().unwrap_or(0),
-        ]
-        .iter()
-        .max()
-        .map(|x| *x)
-        .unwrap_or(0)
+        self.u
+            .iter()
+            .chain(&self.v)
+            .chain(&self.w)
+            .filter_map(|x| x.iter().rposition(|c| *c != S::default()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The number of constraints (rows of the R1CS) the QAP was interpolated from. The same as `degree`, as the
+    /// polynomials themselves may have been trimmed or padded.
+    pub fn num_constraints(&self) -> usize {
+        self.degree()
+    }
+
+    /// Find the quotient `h(x) = (A(x) * B(x) - C(x)) / t(x)` for a witness, where `t(x)` is the vanishing polynomial
+    /// of the QAP's domain. This is the `h(x)` a prover needs, and doesn't depend on a trusted setup.
+    /// Fails with `Groth16Error::NonZeroRemainder` if the witness doesn't satisfy the QAP, with the numerator
+    /// `A(x) * B(x) - C(x)` and `t(x)` attached.
+    pub fn quotient(&self, witness: &[S]) -> Result<Polynomial<S>, Report> {
+        if witness.len() != self.u.len()
+            || witness.len() != self.v.len()
+            || witness.len() != self.w.len()
+        {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.u.len(),
+                got: witness.len()
+            });
+        }
+        if self.domain.is_empty() {
+            return Err(report!(Groth16Error::EmptyCircuit)
+                .attach("QAP has degree zero")
+                .into());
+        }
+
+        let a = Polynomial::linear_combination(&self.u, witness).context("Calculating A(x)")?;
+        let b = Polynomial::linear_combination(&self.v, witness).context("Calculating B(x)")?;
+        let w = Polynomial::linear_combination(&self.w, witness).context("Calculating C(x)")?;
+
+        let numerator = &(&a * &b) - &w;
+        Ok(numerator
+            .divide_by_vanishing(&self.domain)
+            .context("Dividing u(x)*v(x) - w(x) by t to find h")
+            .attach_with(|| format!("A(x) * B(x) - C(x): {:?}", numerator))
+            .attach_with(|| format!("t(x): {:?}", Polynomial::vanishing(&self.domain)))?)
+    }
+
+    /// Evaluate the witness-weighted sums `A(x) = sum(a_i * u_i(x))`, `B(x) = sum(a_i * v_i(x))` and
+    /// `C(x) = sum(a_i * w_i(x))` at `tau`, returning `(A(tau), B(tau), C(tau))`.
+    /// Fails with `Groth16Error::WitnessLength` if the witness doesn't have one element per polynomial.
+    pub fn evaluate_at(&self, witness: &[S], tau: S) -> Result<(S, S, S), Report> {
+        if witness.len() != self.u.len()
+            || witness.len() != self.v.len()
+            || witness.len() != self.w.len()
+        {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.u.len(),
+                got: witness.len()
+            });
+        }
+
+        let a = zip(&self.u, witness)
+            .map(|(u_i, a_i)| u_i.evaluate(&tau) * a_i)
+            .sum();
+        let b = zip(&self.v, witness)
+            .map(|(v_i, a_i)| v_i.evaluate(&tau) * a_i)
+            .sum();
+        let c = zip(&self.w, witness)
+            .map(|(w_i, a_i)| w_i.evaluate(&tau) * a_i)
+            .sum();
+        Ok((a, b, c))
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn verify(&self, witness: &[S]) -> bool {
+        self.verify_rounds(witness, 1)
+    }
+
+    /// Probabilistically check the witness satisfies the QAP, by finding `h(x) = (A(x) * B(x) - C(x)) / t(x)`,
+    /// discarding any remainder, and checking `A(tau) * B(tau) = C(tau) + h(tau) * t(tau)` at `rounds` independent
+    /// random `tau`s, stopping at the first failure.
+    /// A bad witness leaves a non-zero remainder of degree below `n`, so passes each round with probability at most
+    /// `n / |F|`, and all of them with at most `(n / |F|)^rounds`. Extra rounds matter on small fields.
+    #[cfg(feature = "std")]
+    pub fn verify_rounds(&self, witness: &[S], rounds: usize) -> bool {
+        self.verify_rounds_with_rng(witness, rounds, &mut rand::rng())
     }
 
-    pub(crate) fn verify(&self, witness: &Vec<S>) -> bool {
+    /// As `verify_rounds`, but drawing each `tau` from `rng` rather than the thread RNG, which isn't available
+    /// without `std`.
+    pub fn verify_rounds_with_rng<R: rand::Rng>(
+        &self,
+        witness: &[S],
+        rounds: usize,
+        rng: &mut R,
+    ) -> bool {
         if witness.len() != self.u.len()
             || witness.len() != self.v.len()
             || witness.len() != self.w.len()
         {
             return false;
         }
-        let mut rng = rand::rng();
-        let tau = S::from(rng.random_range(0..1000));
 
-        let a: Polynomial<S> = zip(&self.u, witness).map(|(u_i, a_i)| u_i * *a_i).sum();
+        let (Ok(a), Ok(b), Ok(w)) = (
+            Polynomial::linear_combination(&self.u, witness),
+            Polynomial::linear_combination(&self.v, witness),
+            Polynomial::linear_combination(&self.w, witness),
+        ) else {
+            return false;
+        };
 
-        let b: Polynomial<S> = zip(&self.v, witness).map(|(v_i, a_i)| v_i * *a_i).sum();
+        let t = Polynomial::vanishing(&self.domain);
+        let Ok((h, _)) = (&(&a * &b) - &w).div_rem(&t) else {
+            return false;
+        };
+
+        (0..rounds).all(|_| {
+            let tau: S = rand_scalar(rng);
+            let Ok((a_tau, b_tau, w_tau)) = self.evaluate_at(witness, tau) else {
+                return false;
+            };
+            a_tau * b_tau == w_tau + h.evaluate(&tau) * t.evaluate(&tau)
+        })
+    }
+
+    /// Check the witness satisfies the QAP, i.e. `t(x)` divides `A(x) * B(x) - C(x)` exactly, as a diagnostic
+    /// alternative to `verify`. Fails with `Groth16Error::NonZeroRemainder` if it doesn't, with both sides of
+    /// `A(tau) * B(tau) = C(tau) + H(tau) * t(tau)` at a fixed `tau` outside the domain attached along with their
+    /// difference, where `H(x)` is the quotient with the remainder discarded. The difference is the remainder
+    /// evaluated at `tau`, which is also attached.
+    pub fn check(&self, witness: &[S]) -> Result<(), Report> {
+        if witness.len() != self.u.len()
+            || witness.len() != self.v.len()
+            || witness.len() != self.w.len()
+        {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.u.len(),
+                got: witness.len()
+            });
+        }
+        if self.domain.is_empty() {
+            return Err(report!(Groth16Error::EmptyCircuit)
+                .attach("QAP has degree zero")
+                .into());
+        }
+
+        let a = Polynomial::linear_combination(&self.u, witness).context("Calculating A(x)")?;
+        let b = Polynomial::linear_combination(&self.v, witness).context("Calculating B(x)")?;
+        let c = Polynomial::linear_combination(&self.w, witness).context("Calculating C(x)")?;
+        let t = Polynomial::vanishing(&self.domain);
+        let (h, remainder) = (&(&a * &b) - &c)
+            .div_rem(&t)
+            .context("Dividing A(x) * B(x) - C(x) by t(x)")?;
+        if remainder.is_zero() {
+            return Ok(());
+        }
+
+        // Any point outside the domain will do, as long as it's the same every time
+        let mut tau = S::from(self.domain.len() as u64 + 1);
+        while self.domain.contains(&tau) {
+            tau += S::ONE;
+        }
+        let lhs = a.evaluate(&tau) * b.evaluate(&tau);
+        let rhs = c.evaluate(&tau) + h.evaluate(&tau) * t.evaluate(&tau);
+        Err(report!(Groth16Error::NonZeroRemainder)
+            .attach(format!("At tau = {:?}", tau))
+            .attach(format!("A(tau) * B(tau): {:?}", lhs))
+            .attach(format!("C(tau) + H(tau) * t(tau): {:?}", rhs))
+            .attach(format!("Difference: {:?}", lhs - rhs))
+            .attach(format!("Remainder: {:?}", remainder))
+            .context("Checking A(x) * B(x) - C(x) is divisible by t(x)")
+            .into())
+    }
+
+    /// Deterministically check the witness satisfies every constraint, by checking `A(x) * B(x) = C(x)`
+    /// at each of the evaluation points in `domain` the QAP was interpolated over.
+    /// Should accept exactly the witnesses `R1CS::verify` does.
+    pub fn verify_exact(&self, witness: &[S]) -> bool {
+        self.verify_exact_detailed(witness).is_ok()
+    }
+
+    /// As `verify_exact`, but fails with `Groth16Error::UnsatisfiedConstraint` naming the first violated constraint,
+    /// by its label if the circuit has them.
+    pub fn verify_exact_detailed(&self, witness: &[S]) -> Result<(), Report> {
+        if witness.len() != self.u.len()
+            || witness.len() != self.v.len()
+            || witness.len() != self.w.len()
+        {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.u.len(),
+                got: witness.len()
+            });
+        }
 
+        let a: Polynomial<S> = zip(&self.u, witness).map(|(u_i, a_i)| u_i * *a_i).sum();
+        let b: Polynomial<S> = zip(&self.v, witness).map(|(v_i, a_i)| v_i * *a_i).sum();
         let w: Polynomial<S> = zip(&self.w, witness).map(|(w_i, a_i)| w_i * *a_i).sum();
 
-        let ht = &(&a * &b) - &w;
+        for (row, x) in self.domain.iter().enumerate() {
+            if a.evaluate(x) * b.evaluate(x) != w.evaluate(x) {
+                bail!(unsatisfied_constraint(&self.constraint_labels, row));
+            }
+        }
+        Ok(())
+    }
+
+    /// The smallest multiplicative subgroup `1, w, w^2, ..., w^(2^k - 1)` with at least `num_constraints` elements,
+    /// using the field's `2^k`-th root of unity `w`.
+    pub fn roots_of_unity_domain(num_constraints: usize) -> Result<Vec<S>, Report> {
+        let size = num_constraints.next_power_of_two();
+        let root = S::get_root_of_unity(size as u64)
+            .ok_or(report!(Groth16Error::InvalidDomain {
+                needed: size,
+                have: 0
+            }))
+            .attach(format!(
+                "Field has no multiplicative subgroup of size {size}"
+            ))?;
+
+        Ok((0..size).map(|i| root.pow([i as u64])).collect())
+    }
+
+    /// Derive a QAP from an R1CS as `QAP::from` does, but first check the R1CS is well formed.
+    /// Fails with `Groth16Error::EmptyCircuit` if there are no constraints, `Groth16Error::InconsistentMatrices` if
+    /// the matrices are ragged or differently sized, and `Groth16Error::PublicWitnessTooLong` if the public witness
+    /// has more elements than the circuit has wires. `QAP::from` can't fail, so happily interpolates such a circuit,
+    /// which then fails later on in the trusted setup.
+    ///
+    /// This isn't a `TryFrom` impl as that would conflict with the blanket one `From` provides.
+    pub fn try_from_r1cs(r1cs: R1CS<S>) -> Result<Self, Report> {
+        r1cs.check_shape()
+            .context("Checking R1CS matrix dimensions")?;
+        r1cs.check_public_witness_fits()?;
+        check_domain_size::<S>(r1cs.num_constraints())
+            .context("Checking the field is large enough for the circuit")?;
+        Ok(QAP::from(r1cs))
+    }
+
+    /// Recover the R1CS the QAP was derived from, by evaluating each column polynomial at the points of `domain`.
+    /// Interpolation is exact, so `QAP::from(qap.to_r1cs())` gives back `qap` for a QAP made with `QAP::from`.
+    /// For a QAP made with `QAP::from_over_domain` there's a row for every point in the domain, including any
+    /// extra empty constraints it was padded with.
+    pub fn to_r1cs(&self) -> R1CS<S> {
+        let evaluate = |polynomials: &Vec<Polynomial<S>>| -> Vec<Vec<S>> {
+            polynomials
+                .iter()
+                .map(|polynomial| self.domain.iter().map(|x| polynomial.evaluate(x)).collect())
+                .collect()
+        };
+
+        R1CS {
+            L: evaluate(&self.u),
+            R: evaluate(&self.v),
+            O: evaluate(&self.w),
+            public_witness: self.public_witness.clone(),
+            constraint_labels: self.constraint_labels.clone(),
+        }
+    }
+
+    /// Derive a QAP by interpolating each column over the given `domain` rather than `1, 2, ..., n`.
+    /// The domain must have at least one point per constraint, extra points are treated as empty constraints.
+    /// Over a domain from `QAP::roots_of_unity_domain` the vanishing polynomial `t` is `X^n - 1`, which is what
+    /// allows FFT based setup and proving.
+    pub fn from_over_domain(r1cs: R1CS<S>, domain: &[S]) -> Result<Self, Report> {
+        r1cs.check_public_witness_fits()?;
+        let num_constraints = r1cs.num_constraints();
+        if domain.len() < num_constraints {
+            bail!(Groth16Error::InvalidDomain {
+                needed: num_constraints,
+                have: domain.len()
+            });
+        }
+        check_domain_size::<S>(domain.len())
+            .context("Checking the field is large enough for the domain")?;
+
+        let interpolate = |matrix: &Vec<Vec<S>>| -> Vec<Polynomial<S>> {
+            matrix
+                .iter()
+                .map(|column| {
+                    let mut column = column.clone();
+                    column.resize(domain.len(), S::default());
+                    Polynomial::interpolate(domain, &column)
+                })
+                .collect()
+        };
+
+        Ok(QAP {
+            u: interpolate(&r1cs.L),
+            v: interpolate(&r1cs.R),
+            w: interpolate(&r1cs.O),
+            public_witness: r1cs.public_witness,
+            constraint_labels: r1cs.constraint_labels,
+            domain: domain.to_vec(),
+        })
+    }
+}
 
-        a.evaluate(&tau) * b.evaluate(&tau) == w.evaluate(&tau) + ht.evaluate(&tau)
+fn unsatisfied_constraint(labels: &[String], index: usize) -> Groth16Error {
+    Groth16Error::UnsatisfiedConstraint {
+        index,
+        label: labels.get(index).cloned(),
     }
 }
 
@@ -133,31 +928,74 @@ impl<S: FftField> From<R1CS<S>> for QAP<S> {
             u: r1cs
                 .L
                 .iter()
-                .map(Polynomial::interpolate_from_vector)
+                .map(|column| Polynomial::interpolate_from_vector(column))
                 .collect(),
             v: r1cs
                 .R
                 .iter()
-                .map(Polynomial::interpolate_from_vector)
+                .map(|column| Polynomial::interpolate_from_vector(column))
                 .collect(),
             w: r1cs
                 .O
                 .iter()
-                .map(Polynomial::interpolate_from_vector)
+                .map(|column| Polynomial::interpolate_from_vector(column))
+                .collect(),
+            domain: (1..r1cs.num_constraints() + 1)
+                .map(|x| S::from(x as u128))
                 .collect(),
             public_witness: r1cs.public_witness,
+            constraint_labels: r1cs.constraint_labels,
+        }
+    }
+}
+
+impl<S: FftField> From<SparseR1CS<S>> for QAP<S> {
+    /// Interpolates each Lagrange basis polynomial once, then combines them using only the non-zero entries of each
+    /// column, giving the same QAP as converting the dense `R1CS`.
+    fn from(sparse: SparseR1CS<S>) -> Self {
+        let n = sparse.num_constraints;
+        let basis: Vec<Polynomial<S>> = (0..n)
+            .map(|row| {
+                let mut y = vec![S::default(); n];
+                y[row] = S::ONE;
+                Polynomial::interpolate_from_vector(&y)
+            })
+            .collect();
+
+        let interpolate = |matrix: &Vec<Vec<(usize, S)>>| -> Vec<Polynomial<S>> {
+            matrix
+                .iter()
+                .map(|column| {
+                    column.iter().fold(
+                        Polynomial::new(vec![S::default(); n]),
+                        |acc, (row, value)| &acc + &(&basis[*row] * *value),
+                    )
+                })
+                .collect()
+        };
+
+        QAP {
+            u: interpolate(&sparse.l),
+            v: interpolate(&sparse.r),
+            w: interpolate(&sparse.o),
+            public_witness: sparse.public_witness,
+            constraint_labels: sparse.constraint_labels,
+            domain: (1..n + 1).map(|x| S::from(x as u128)).collect(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::circuits::{QAP, R1CS};
+    use crate::circuits::{ConstraintStatus, QAP, R1CS, SparseR1CS, check_domain_size};
+    use crate::error::Groth16Error;
     use crate::polynomial::Polynomial;
-    use ark_ff::{Fp64, MontBackend};
+    use ark_ff::{Field as _, Fp64, MontBackend};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use log::debug;
     use rand::Rng;
     use rootcause::Report;
+    use std::iter::zip;
 
     #[derive(ark_ff::MontConfig)]
     #[modulus = "641"]
@@ -220,6 +1058,7 @@ mod tests {
             R,
             O,
             public_witness: Vec::new(),
+            constraint_labels: Vec::new(),
         };
 
         let qap = QAP::from(r1cs);
@@ -250,6 +1089,8 @@ mod tests {
                 Polynomial::from(vec![4, 634, 324, 320]),
             ],
             public_witness: Vec::new(),
+            constraint_labels: Vec::new(),
+            domain: vec![1, 2, 3, 4].into_iter().map(Field::from).collect(),
         };
 
         assert_eq!(qap, known_good)
@@ -307,4 +1148,939 @@ mod tests {
         assert!(r1cs.verify(&w)?);
         Ok(())
     }
+
+    #[test]
+    fn r1cs_and_qap_verification_agree() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let qap = QAP::from(r1cs.clone());
+        let mut rng = rand::rng();
+
+        for _ in 0..200 {
+            let x = Field::from(rng.random_range(0..641));
+            let y = Field::from(rng.random_range(0..641));
+            let z = Field::from(rng.random_range(0..641));
+            let u = Field::from(rng.random_range(0..641));
+            let mut w = vec![Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+
+            assert!(r1cs.verify(&w)?);
+            assert!(qap.verify_exact(&w));
+
+            // Perturb a random element, which may or may not still satisfy the constraints
+            let i = rng.random_range(0..w.len());
+            w[i] += Field::from(rng.random_range(1..641));
+            debug!("Checking perturbed witness {:?}", w);
+            assert_eq!(r1cs.verify(&w)?, qap.verify_exact(&w));
+
+            // And a completely random witness
+            let w: Vec<Field> = (0..8)
+                .map(|_| Field::from(rng.random_range(0..641)))
+                .collect();
+            assert_eq!(r1cs.verify(&w)?, qap.verify_exact(&w));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_and_dense_agree() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let dense: R1CS<Field> = R1CS::new(l, r, o, vec![1]);
+        let sparse = SparseR1CS::from(dense.clone());
+        assert_eq!(sparse.l[2], vec![(0, Field::from(1))]);
+        assert!(sparse.l[0].is_empty());
+        assert_eq!(sparse.num_constraints, 3);
+
+        assert_eq!(QAP::from(sparse.clone()), QAP::from(dense.clone()));
+        let round_tripped = R1CS::from(sparse.clone());
+        assert_eq!(round_tripped.L, dense.L);
+        assert_eq!(round_tripped.R, dense.R);
+        assert_eq!(round_tripped.O, dense.O);
+
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let x = Field::from(rng.random_range(0..641));
+            let y = Field::from(rng.random_range(0..641));
+            let z = Field::from(rng.random_range(0..641));
+            let u = Field::from(rng.random_range(0..641));
+            let mut w = vec![Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+            assert!(sparse.verify(&w)?);
+
+            let i = rng.random_range(0..w.len());
+            w[i] += Field::from(rng.random_range(1..641));
+            assert_eq!(sparse.verify(&w)?, dense.verify(&w)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn qap_degree_ignores_trailing_zeros() {
+        let l = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let r = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let o = vec![vec![0, 0], vec![0, 0], vec![1, 0], vec![0, 1]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let qap = QAP::from(r1cs);
+        assert_eq!(qap.degree(), 2);
+        assert_eq!(qap.max_polynomial_degree(), 1);
+
+        // As if deserialized from data that padded the coefficients
+        let mut padded = qap.clone();
+        for poly in padded
+            .u
+            .iter_mut()
+            .chain(&mut padded.v)
+            .chain(&mut padded.w)
+        {
+            *poly = Polynomial::new([poly.coefficients(), &[Field::from(0); 3]].concat());
+        }
+        assert_eq!(padded.u[1].degree(), 4);
+        assert_eq!(padded.degree(), 2);
+        assert_eq!(padded.max_polynomial_degree(), 1);
+    }
+
+    #[test]
+    fn qap_over_roots_of_unity() -> Result<(), Report> {
+        let l = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let r = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let o = vec![vec![0, 0], vec![0, 0], vec![1, 0], vec![0, 1]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+
+        // x^2 = y, y^2 = z
+        let x = Field::from(5);
+        let w = vec![Field::from(1), x, x * x, x * x * x * x];
+
+        let domain: Vec<Field> = QAP::roots_of_unity_domain(3)?;
+        assert_eq!(domain.len(), 4);
+        assert_eq!(domain[0], Field::from(1));
+        assert!(domain.iter().all(|d| d.pow([4]) == Field::from(1)));
+
+        let qap = QAP::from_over_domain(r1cs.clone(), &domain)?;
+        assert!(qap.verify_exact(&w));
+        assert!(QAP::from(r1cs.clone()).verify_exact(&w));
+
+        let mut bad = w.clone();
+        bad[3] += Field::from(1);
+        assert!(!qap.verify_exact(&bad));
+
+        let error = QAP::from_over_domain(r1cs, &domain[..1]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::InvalidDomain { needed: 2, have: 1 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_rounds_rejects_bad_witnesses() {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let qap: QAP<Field> = QAP::from(R1CS::new(l, r, o, Vec::<i32>::new()));
+        let mut rng = rand::rng();
+        let mut single_round_passes = 0;
+
+        for _ in 0..10000 {
+            let w: Vec<Field> = (0..8)
+                .map(|_| Field::from(rng.random_range(0..641)))
+                .collect();
+            if qap.verify_exact(&w) {
+                continue;
+            }
+
+            // On a 641 element field a bad witness's remainder has a root at tau every few hundred tries
+            if qap.verify_rounds_with_rng(&w, 1, &mut rng) {
+                single_round_passes += 1;
+            }
+            assert!(!qap.verify_rounds_with_rng(&w, 20, &mut rng));
+        }
+        debug!("Single round check passed {single_round_passes} bad witnesses");
+        assert!(single_round_passes > 0);
+
+        let x = Field::from(rng.random_range(0..641));
+        let y = Field::from(rng.random_range(0..641));
+        let w = vec![Field::from(1), x * y * x * y, x, y, x, y, x * y, x * y];
+        assert!(qap.verify_rounds_with_rng(&w, 20, &mut rng));
+    }
+
+    #[test]
+    fn qap_to_r1cs_round_trips() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs.clone());
+
+        assert_eq!(qap.to_r1cs(), r1cs);
+        assert_eq!(QAP::from(qap.to_r1cs()), qap);
+
+        let domain = QAP::<Field>::roots_of_unity_domain(3)?;
+        let qap = QAP::from_over_domain(r1cs.clone(), &domain)?;
+        let padded = qap.to_r1cs();
+        assert_eq!(padded.num_constraints(), 4);
+        assert_eq!(QAP::from_over_domain(padded, &domain)?, qap);
+        Ok(())
+    }
+
+    #[test]
+    fn qap_evaluate_at() -> Result<(), Report> {
+        let l = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let r = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let o = vec![vec![0, 0], vec![0, 0], vec![1, 0], vec![0, 1]];
+        let qap: QAP<Field> = QAP::from(R1CS::new(l, r, o, Vec::<i32>::new()));
+        let w = [1, 3, 9, 81].map(Field::from);
+        let tau = Field::from(17);
+
+        let a: Polynomial<Field> = zip(&qap.u, &w).map(|(u_i, a_i)| u_i * *a_i).sum();
+        let b: Polynomial<Field> = zip(&qap.v, &w).map(|(v_i, a_i)| v_i * *a_i).sum();
+        let c: Polynomial<Field> = zip(&qap.w, &w).map(|(w_i, a_i)| w_i * *a_i).sum();
+        assert_eq!(
+            qap.evaluate_at(&w, tau)?,
+            (a.evaluate(&tau), b.evaluate(&tau), c.evaluate(&tau))
+        );
+
+        let error = qap.evaluate_at(&w[..3], tau).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::WitnessLength {
+                expected: 4,
+                got: 3
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn qap_check_attaches_both_sides() -> Result<(), Report> {
+        let l = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let r = vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![0, 0]];
+        let o = vec![vec![0, 0], vec![0, 0], vec![1, 0], vec![0, 1]];
+        let qap: QAP<Field> = QAP::from(R1CS::new(l, r, o, Vec::<i32>::new()));
+        qap.check(&[1, 3, 9, 81].map(Field::from))?;
+
+        let error = qap.check(&[1, 3, 9, 80].map(Field::from)).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+        let attachments: Vec<&String> = error
+            .iter_reports()
+            .flat_map(|report| report.attachments().iter())
+            .filter_map(|attachment| attachment.downcast_inner::<String>())
+            .collect();
+        for side in [
+            "A(tau) * B(tau):",
+            "C(tau) + H(tau) * t(tau):",
+            "Difference:",
+        ] {
+            assert!(
+                attachments.iter().any(|a| a.starts_with(side)),
+                "Missing {side} in {attachments:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn domain_must_fit_in_field() -> Result<(), Report> {
+        // The test field has characteristic 641, so has room for 640 distinct domain points
+        check_domain_size::<Field>(640)?;
+
+        let column = vec![vec![0; 641]];
+        let r1cs: R1CS<Field> = R1CS::new(column.clone(), column.clone(), column, vec![0]);
+        let error = QAP::try_from_r1cs(r1cs).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::InvalidDomain {
+                needed: 641,
+                have: 641
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn r1cs_serialisation_round_trips() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 24]);
+
+        let json = serde_json::to_string(&r1cs)?;
+        let deserialized: R1CS<Field> = serde_json::from_str(&json)?;
+        assert_eq!(deserialized, r1cs);
+        assert_eq!(deserialized.L.len(), 8);
+        assert_eq!(deserialized.L[2].len(), 3);
+        assert_eq!(QAP::from(deserialized), QAP::from(r1cs.clone()));
+
+        let mut bytes = vec![];
+        r1cs.serialize_compressed(&mut bytes)?;
+        assert_eq!(
+            R1CS::<Field>::deserialize_compressed(bytes.as_slice())?,
+            r1cs
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn partial_witness_verification() {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let (x, y, z, u) = (
+            Field::from(2),
+            Field::from(3),
+            Field::from(4),
+            Field::from(5),
+        );
+        let w = [Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+
+        // v2 = z * u is unknown, so the second and third constraints can't be checked yet
+        let mut partial: Vec<Option<Field>> = w.iter().copied().map(Some).collect();
+        partial[7] = None;
+        let report = r1cs.verify_partial(&partial);
+        assert_eq!(
+            report.constraints,
+            vec![
+                ConstraintStatus::Satisfied,
+                ConstraintStatus::Undetermined,
+                ConstraintStatus::Undetermined
+            ]
+        );
+        assert!(!report.is_satisfied());
+
+        // A wrong v1 is caught even though v2 is unknown
+        partial[6] = Some(x * y + Field::from(1));
+        let report = r1cs.verify_partial(&partial);
+        assert_eq!(report.with_status(ConstraintStatus::Violated), vec![0]);
+        assert_eq!(
+            report.with_status(ConstraintStatus::Undetermined),
+            vec![1, 2]
+        );
+
+        let complete: Vec<Option<Field>> = w.iter().copied().map(Some).collect();
+        assert!(r1cs.verify_partial(&complete).is_satisfied());
+        assert_eq!(
+            r1cs.verify_partial(&complete[..6])
+                .with_status(ConstraintStatus::Undetermined),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn witness_solver() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let (x, y, z, u) = (
+            Field::from(2),
+            Field::from(3),
+            Field::from(4),
+            Field::from(5),
+        );
+        let (v1, v2) = (x * y, z * u);
+        let out = v1 * v2;
+
+        let solved = r1cs.solve(&[
+            Some(Field::from(1)),
+            None,
+            Some(x),
+            Some(y),
+            Some(z),
+            Some(u),
+        ])?;
+        assert_eq!(solved, vec![Field::from(1), out, x, y, z, u, v1, v2]);
+        assert!(r1cs.verify(&solved)?);
+
+        // Division: an input can be recovered from the output and the other input
+        let solved = r1cs.solve(&[
+            Some(Field::from(1)),
+            Some(out),
+            None,
+            Some(y),
+            Some(z),
+            Some(u),
+        ])?;
+        assert_eq!(solved[2], x);
+
+        let err = r1cs
+            .solve(&[Some(Field::from(1)), None, Some(x), Some(y), Some(z)])
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::UnsolvedWire { wire: 1 })
+        );
+
+        let err = r1cs
+            .solve(&[
+                Some(Field::from(1)),
+                Some(out + Field::from(1)),
+                Some(x),
+                Some(y),
+                Some(z),
+                Some(u),
+            ])
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::UnsatisfiedConstraint {
+                index: 2,
+                label: None
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn over_long_public_witness_errors() -> Result<(), Report> {
+        let l = vec![vec![1], vec![0]];
+        let r = vec![vec![1], vec![0]];
+        let o = vec![vec![0], vec![1]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 2, 3]);
+        let expected = Groth16Error::PublicWitnessTooLong {
+            public: 3,
+            wires: 2,
+        };
+
+        let err = QAP::try_from_r1cs(r1cs.clone()).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&expected));
+        let err = QAP::from_over_domain(r1cs.clone(), &[Field::from(1)]).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&expected));
+
+        let fits = R1CS {
+            public_witness: vec![Field::from(1), Field::from(2)],
+            ..r1cs.clone()
+        };
+        assert_eq!(QAP::try_from_r1cs(fits.clone())?, QAP::from(fits));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_r1cs_rejected() -> Result<(), Report> {
+        let ragged: R1CS<Field> = R1CS::new(
+            vec![vec![1, 0], vec![0]],
+            vec![vec![1, 0], vec![0, 1]],
+            vec![vec![0, 1], vec![1, 0]],
+            Vec::<i32>::new(),
+        );
+        let err = QAP::try_from_r1cs(ragged).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentMatrices)
+        );
+
+        let mismatched: R1CS<Field> = R1CS::new(
+            vec![vec![1, 0], vec![0, 1]],
+            vec![vec![1, 0], vec![0, 1], vec![0, 0]],
+            vec![vec![0, 1], vec![1, 0]],
+            Vec::<i32>::new(),
+        );
+        let err = QAP::try_from_r1cs(mismatched).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentMatrices)
+        );
+
+        for empty in [
+            R1CS::<Field>::new(Vec::<Vec<i32>>::new(), vec![], vec![], Vec::<i32>::new()),
+            R1CS::new(
+                vec![Vec::<i32>::new()],
+                vec![vec![]],
+                vec![vec![]],
+                Vec::<i32>::new(),
+            ),
+        ] {
+            let err = QAP::try_from_r1cs(empty).unwrap_err();
+            assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::EmptyCircuit));
+        }
+
+        let valid: R1CS<Field> = R1CS::new(
+            vec![vec![1, 0], vec![0, 1]],
+            vec![vec![1, 0], vec![0, 1]],
+            vec![vec![0, 1], vec![1, 0]],
+            vec![1],
+        );
+        assert_eq!(QAP::try_from_r1cs(valid.clone())?, QAP::from(valid));
+        Ok(())
+    }
+
+    #[test]
+    fn r1cs_dimensions() {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1]);
+        assert_eq!(r1cs.num_constraints(), 3);
+        assert_eq!(r1cs.num_variables(), 8);
+
+        let empty = R1CS::<Field>::new(Vec::<Vec<i32>>::new(), vec![], vec![], Vec::<i32>::new());
+        assert_eq!(empty.num_constraints(), 0);
+        assert_eq!(empty.num_variables(), 0);
+
+        // Ragged columns report the tallest, and are only rejected when converting to a QAP
+        let ragged = R1CS::<Field>::new(
+            vec![vec![1, 0], vec![0, 1, 0]],
+            vec![vec![1, 0], vec![0, 1]],
+            vec![vec![0, 1], vec![1, 0]],
+            Vec::<i32>::new(),
+        );
+        assert_eq!(ragged.num_constraints(), 3);
+        let err = QAP::try_from_r1cs(ragged).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentMatrices)
+        );
+    }
+
+    #[test]
+    fn merge_circuits() -> Result<(), Report> {
+        // out = a * b * c * d, with wires 1, out, a, b, c, d, a * b, c * d
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+
+        // Share the constant wire, and give the second copy's other wires new columns
+        let wire_map: Vec<usize> = [0].into_iter().chain(8..15).collect();
+        let merged = r1cs.merge(&r1cs, &wire_map)?;
+        assert_eq!(merged.num_constraints(), 6);
+        assert_eq!(merged.num_variables(), 15);
+        assert_eq!(merged.public_witness, r1cs.public_witness);
+        assert!(merged.constraint_labels.is_empty());
+
+        let first = [1, 120, 2, 3, 4, 5, 6, 20];
+        let second = [24, 1, 2, 3, 4, 2, 12];
+        let witness: Vec<Field> = first
+            .iter()
+            .chain(&second)
+            .map(|x| Field::from(*x))
+            .collect();
+        assert!(merged.verify(&witness)?);
+        let mut bad = witness.clone();
+        bad[9] += Field::from(1);
+        assert!(!merged.verify(&bad)?);
+        assert!(QAP::try_from_r1cs(merged.clone())?.verify_exact(&witness));
+
+        // Sharing every wire repeats the constraints on the same witness
+        let shared = r1cs.merge(&r1cs, &(0..8).collect::<Vec<_>>())?;
+        assert_eq!(shared.num_variables(), 8);
+        assert!(shared.verify(&witness[..8])?);
+
+        let labelled =
+            r1cs.clone()
+                .with_constraint_labels(vec!["ab".into(), "cd".into(), "out".into()]);
+        assert_eq!(
+            r1cs.merge(&labelled, &wire_map)?.constraint_labels,
+            vec!["", "", "", "ab", "cd", "out"]
+        );
+
+        let err = r1cs.merge(&r1cs, &wire_map[1..]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::WireMapLength {
+                expected: 8,
+                got: 7
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_over_small_field_agrees() -> Result<(), Report> {
+        type Native = ark_mnt6_753::Fr;
+        // out = a * b * c * d, for public out, plus (out - 120) * 1 = 0 to exercise a negative coefficient
+        let column = |rows: [i64; 4]| rows.map(Native::from).to_vec();
+        let l = vec![
+            column([0, 0, 0, -120]),
+            column([0, 0, 0, 1]),
+            column([1, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 1, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 1, 0]),
+            column([0, 0, 0, 0]),
+        ];
+        let r = vec![
+            column([0, 0, 0, 1]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([1, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 1, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 1, 0]),
+        ];
+        let o = vec![
+            column([0, 0, 0, 0]),
+            column([0, 0, 1, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([0, 0, 0, 0]),
+            column([1, 0, 0, 0]),
+            column([0, 1, 0, 0]),
+        ];
+        let r1cs = R1CS::new(l, r, o, vec![1, 120]);
+
+        for witness in [[1, 120, 2, 3, 4, 5, 6, 20], [1, 120, 2, 3, 4, 5, 7, 20]] {
+            let native = r1cs.verify(&witness.map(Native::from))?;
+            let small = r1cs.verify_over(&witness.map(Field::from))?;
+            assert_eq!(native, small);
+        }
+        assert!(r1cs.verify_over(&[1, 120, 2, 3, 4, 5, 6, 20].map(Field::from))?);
+
+        let err = r1cs.verify_over(&[Field::from(1)]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::WitnessLength {
+                expected: 8,
+                got: 1
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn labelled_constraint_errors() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        let labels = vec![
+            "v1 = x * y".to_string(),
+            "v2 = z * u".to_string(),
+            "r = v1 * v2".to_string(),
+        ];
+        let r1cs: R1CS<Field> =
+            R1CS::new(l, r, o, Vec::<i32>::new()).with_constraint_labels(labels);
+        let qap = QAP::from(r1cs.clone());
+
+        let (x, y, z, u) = (
+            Field::from(2),
+            Field::from(3),
+            Field::from(4),
+            Field::from(5),
+        );
+        let mut w = vec![Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+        r1cs.verify_detailed(&w)?;
+        qap.verify_exact_detailed(&w)?;
+
+        // Break v2, which violates both the second and third constraints, the second should be reported
+        w[7] += Field::from(1);
+        let expected = Groth16Error::UnsatisfiedConstraint {
+            index: 1,
+            label: Some("v2 = z * u".to_string()),
+        };
+        let error = r1cs.verify_detailed(&w).unwrap_err();
+        assert_eq!(Groth16Error::find(&error), Some(&expected));
+        assert!(format!("{error}").contains("v2 = z * u"));
+        let error = qap.verify_exact_detailed(&w).unwrap_err();
+        assert_eq!(Groth16Error::find(&error), Some(&expected));
+
+        // Without labels just the index is reported
+        let unlabelled = r1cs.with_constraint_labels(Vec::new());
+        let error = unlabelled.verify_detailed(&w).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::UnsatisfiedConstraint {
+                index: 1,
+                label: None
+            })
+        );
+        Ok(())
+    }
 }
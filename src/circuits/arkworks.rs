@@ -0,0 +1,157 @@
+use crate::circuits::R1CS;
+use crate::error::Groth16Error;
+use ark_ff::FftField;
+use ark_relations::r1cs::{ConstraintSystemRef, Matrix};
+use rootcause::{Report, report};
+
+impl<S: FftField> R1CS<S> {
+    /// Read the constraints out of an arkworks constraint system, e.g. after running a `ConstraintSynthesizer` on it.
+    /// The system is finalized first, inlining any symbolic linear combinations.
+    ///
+    /// arkworks numbers the instance (public) variables first, starting with the constant 1, then the witness
+    /// (private) variables, and that order is kept here. The public witness is the instance assignment, so the system
+    /// must have been synthesized in prove mode with matrices constructed, which is the default for
+    /// `ConstraintSystem::new_ref`. Otherwise fails with `Groth16Error::IncompleteConstraintSystem`.
+    /// The full witness to prove with is given by `R1CS::witness_from_constraint_system`.
+    pub fn from_constraint_system(cs: ConstraintSystemRef<S>) -> Result<R1CS<S>, Report> {
+        cs.finalize();
+        let matrices = cs.to_matrices().ok_or_else(|| {
+            report!(Groth16Error::IncompleteConstraintSystem)
+                .attach("Constraint system was synthesized without constructing matrices")
+        })?;
+        let public_witness = borrow(&cs)?.instance_assignment.clone();
+        if public_witness.len() != matrices.num_instance_variables {
+            return Err(report!(Groth16Error::IncompleteConstraintSystem)
+                .attach("Constraint system has no instance assignment")
+                .into());
+        }
+
+        let num_variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        let columns = |matrix: &Matrix<S>| -> Vec<Vec<S>> {
+            let mut columns = vec![vec![S::default(); matrices.num_constraints]; num_variables];
+            for (row, terms) in matrix.iter().enumerate() {
+                for (coefficient, variable) in terms {
+                    columns[*variable][row] += *coefficient;
+                }
+            }
+            columns
+        };
+
+        Ok(R1CS {
+            L: columns(&matrices.a),
+            R: columns(&matrices.b),
+            O: columns(&matrices.c),
+            public_witness,
+            constraint_labels: Vec::new(),
+        })
+    }
+
+    /// The full witness for `R1CS::from_constraint_system`, i.e. the instance assignment followed by the witness
+    /// assignment. Fails with `Groth16Error::IncompleteConstraintSystem` if the system was synthesized in setup
+    /// mode, so has no assignments.
+    pub fn witness_from_constraint_system(cs: &ConstraintSystemRef<S>) -> Result<Vec<S>, Report> {
+        let system = borrow(cs)?;
+        if system.instance_assignment.len() != system.num_instance_variables
+            || system.witness_assignment.len() != system.num_witness_variables
+        {
+            return Err(report!(Groth16Error::IncompleteConstraintSystem)
+                .attach("Constraint system has no assignment")
+                .into());
+        }
+        Ok([
+            system.instance_assignment.as_slice(),
+            &system.witness_assignment,
+        ]
+        .concat())
+    }
+}
+
+fn borrow<S: FftField>(
+    cs: &ConstraintSystemRef<S>,
+) -> Result<std::cell::Ref<'_, ark_relations::r1cs::ConstraintSystem<S>>, Report> {
+    cs.borrow().ok_or_else(|| {
+        report!(Groth16Error::IncompleteConstraintSystem)
+            .attach("Constraint system reference is None")
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::{QAP, R1CS};
+    use crate::error::Groth16Error;
+    use crate::groth16::TrustedSetupOutput;
+    use ark_mnt6_753::MNT6_753;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{
+        ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError,
+        SynthesisMode, Variable,
+    };
+    use rootcause::Report;
+
+    type Field = ark_mnt6_753::Fr;
+
+    /// Knowledge of `x` with `x^3 + x + 5 = out`, for public `out`
+    struct Cubic {
+        x: Field,
+    }
+
+    impl ConstraintSynthesizer<Field> for Cubic {
+        fn generate_constraints(
+            self,
+            cs: ConstraintSystemRef<Field>,
+        ) -> Result<(), SynthesisError> {
+            let x_value = self.x;
+            let x_squared_value = x_value * x_value;
+            let x_cubed_value = x_squared_value * x_value;
+            let out = cs.new_input_variable(|| Ok(x_cubed_value + x_value + Field::from(5)))?;
+            let x = cs.new_witness_variable(|| Ok(x_value))?;
+            let x_squared = cs.new_witness_variable(|| Ok(x_squared_value))?;
+            let x_cubed = cs.new_witness_variable(|| Ok(x_cubed_value))?;
+
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_squared)?;
+            cs.enforce_constraint(lc!() + x_squared, lc!() + x, lc!() + x_cubed)?;
+            cs.enforce_constraint(
+                lc!() + x_cubed + x + (Field::from(5), Variable::One),
+                lc!() + Variable::One,
+                lc!() + out,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn prove_arkworks_circuit() -> Result<(), Report> {
+        let cs = ConstraintSystem::new_ref();
+        Cubic { x: Field::from(3) }.generate_constraints(cs.clone())?;
+        assert!(cs.is_satisfied()?);
+
+        let r1cs = R1CS::from_constraint_system(cs.clone())?;
+        let witness = R1CS::witness_from_constraint_system(&cs)?;
+        assert_eq!(r1cs.num_constraints(), 3);
+        assert_eq!(r1cs.num_variables(), 5);
+        assert_eq!(r1cs.public_witness, vec![Field::from(1), Field::from(35)]);
+        assert!(r1cs.verify(&witness)?);
+
+        let trusted_setup: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new(QAP::try_from_r1cs(r1cs.clone())?)?;
+        let proof = trusted_setup.prove(&witness)?;
+        assert!(proof.verify(&trusted_setup, &r1cs.public_witness)?);
+        assert!(!proof.verify(&trusted_setup, &[Field::from(1), Field::from(36)])?);
+
+        let setup_cs = ConstraintSystem::new_ref();
+        setup_cs.set_mode(SynthesisMode::Setup);
+        Cubic { x: Field::from(3) }.generate_constraints(setup_cs.clone())?;
+        let err = R1CS::from_constraint_system(setup_cs.clone()).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::IncompleteConstraintSystem)
+        );
+        let err = R1CS::witness_from_constraint_system(&setup_cs).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::IncompleteConstraintSystem)
+        );
+        Ok(())
+    }
+}
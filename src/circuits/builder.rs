@@ -0,0 +1,209 @@
+use crate::circuits::R1CS;
+use crate::error::Groth16Error;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use ark_ff::FftField;
+use rootcause::{Report, bail, report};
+
+/// Builds an `R1CS` from named variables and `a * b = c` constraints between them, rather than by writing out the
+/// column-major matrices by hand.
+///
+/// In the built circuit the public inputs come first, then the private variables, each in the order they were added.
+/// That's the order the witness must be given in. Like the rest of the crate, nothing adds the constant 1 wire for you,
+/// so add it as the first public input if the circuit needs it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Builder<S: FftField> {
+    /// Name of each variable in the order added, with its value if it's a public input
+    variables: Vec<(String, Option<S>)>,
+    /// Names of the `a`, `b`, and `c` of each `a * b = c` constraint
+    constraints: Vec<[String; 3]>,
+}
+
+impl<S: FftField> Default for Builder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: FftField> Builder<S> {
+    /// A builder with no variables or constraints.
+    pub fn new() -> Self {
+        Builder {
+            variables: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Add a public input called `name`, whose `value` becomes part of the circuit's public witness.
+    pub fn public_input<W>(mut self, name: &str, value: W) -> Self
+    where
+        S: From<W>,
+    {
+        self.variables
+            .push((name.to_string(), Some(S::from(value))));
+        self
+    }
+
+    /// Add a private variable called `name`.
+    pub fn variable(mut self, name: &str) -> Self {
+        self.variables.push((name.to_string(), None));
+        self
+    }
+
+    /// Add the constraint `a * b = c` between the variables with these names.
+    /// The names are only looked up by `build`, so variables can be added after the constraints that use them.
+    pub fn constrain(mut self, a: &str, b: &str, c: &str) -> Self {
+        self.constraints
+            .push([a.to_string(), b.to_string(), c.to_string()]);
+        self
+    }
+
+    /// Number of variables added so far, i.e. the length of the witness for the built circuit.
+    pub fn num_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Number of constraints added so far.
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Emit the `R1CS`, with a column per variable and a row per constraint.
+    /// Fails with `Groth16Error::DuplicateVariable` if two variables share a name, or
+    /// `Groth16Error::UnknownVariable` if a constraint refers to a name that was never added.
+    pub fn build(self) -> Result<R1CS<S>, Report> {
+        let (public, private): (Vec<_>, Vec<_>) = self
+            .variables
+            .into_iter()
+            .partition(|(_, value)| value.is_some());
+
+        let mut wires = BTreeMap::new();
+        for (wire, (name, _)) in public.iter().chain(&private).enumerate() {
+            if wires.insert(name.as_str(), wire).is_some() {
+                bail!(Groth16Error::DuplicateVariable { name: name.clone() });
+            }
+        }
+
+        let num_wires = wires.len();
+        let num_constraints = self.constraints.len();
+        let mut l = vec![vec![S::default(); num_constraints]; num_wires];
+        let mut r = l.clone();
+        let mut o = l.clone();
+        for (row, names) in self.constraints.iter().enumerate() {
+            for (matrix, name) in [&mut l, &mut r, &mut o].into_iter().zip(names) {
+                let wire = *wires.get(name.as_str()).ok_or_else(|| {
+                    report!(Groth16Error::UnknownVariable { name: name.clone() }).attach(format!(
+                        "In constraint {row}: {} * {} = {}",
+                        names[0], names[1], names[2]
+                    ))
+                })?;
+                matrix[wire][row] = S::ONE;
+            }
+        }
+
+        Ok(R1CS {
+            L: l,
+            R: r,
+            O: o,
+            public_witness: public.into_iter().filter_map(|(_, value)| value).collect(),
+            constraint_labels: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::{Builder, R1CS};
+    use crate::error::Groth16Error;
+    use rootcause::Report;
+
+    type Field = ark_mnt6_753::Fr;
+
+    #[test]
+    fn builder_matches_hand_built_circuit() -> Result<(), Report> {
+        // out = a * b * c * d, for public out
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+        let expected: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+
+        let builder = Builder::<Field>::new()
+            .public_input("one", 1)
+            .variable("a")
+            .variable("b")
+            .variable("c")
+            .variable("d")
+            .variable("ab")
+            .variable("cd")
+            .constrain("a", "b", "ab")
+            .constrain("c", "d", "cd")
+            .constrain("ab", "cd", "out")
+            .public_input("out", 120);
+        assert_eq!(builder.num_variables(), 8);
+        assert_eq!(builder.num_constraints(), 3);
+        let r1cs = builder.build()?;
+        assert_eq!(r1cs, expected);
+
+        let witness: Vec<Field> = [1, 120, 2, 3, 4, 5, 6, 20].map(Field::from).to_vec();
+        assert!(r1cs.verify(&witness)?);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_rejects_bad_names() {
+        let err = Builder::<Field>::new()
+            .variable("a")
+            .constrain("a", "a", "b")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::UnknownVariable {
+                name: "b".to_string()
+            })
+        );
+
+        let err = Builder::<Field>::new()
+            .public_input("a", 4)
+            .variable("a")
+            .constrain("a", "a", "a")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::DuplicateVariable {
+                name: "a".to_string()
+            })
+        );
+    }
+}
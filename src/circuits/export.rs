@@ -0,0 +1,199 @@
+use crate::circuits::R1CS;
+use ark_ff::{BigInteger, PrimeField};
+use rootcause::Report;
+use rootcause::prelude::ResultExt;
+use std::io::Write;
+
+const MAGIC: &[u8; 4] = b"r1cs";
+const VERSION: u32 = 1;
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+const WIRE_LABELS_SECTION: u32 = 3;
+
+/// Write the constraint system in the iden3 binary `.r1cs` format produced by circom, which snarkjs reads.
+///
+/// There's deliberately no gnark or bellman exporter. gnark serializes a constraint system as the CBOR encoding of
+/// its internal `System` struct, which changes between gnark versions and isn't specified anywhere to write against,
+/// and bellman has no serialized constraint system at all, as its circuits are Rust code. The iden3 format is
+/// documented and stable, so it's the one exported here. It isn't checked against any external tool, only against
+/// the layout in iden3's r1csfile specification.
+///
+/// The file is the magic `r1cs`, a version, and three sections: a header with the field size, prime, and wire and
+/// constraint counts, the constraints as the `A`, `B`, and `C` linear combinations of each row, and a map from wires
+/// to labels. All integers are little-endian, and field elements are written in canonical (not Montgomery) form as
+/// little-endian bytes, padded to a multiple of 8 bytes.
+///
+/// Wire `i` in the file is element `i` of this crate's witness. Like circom, wire 0 is expected to be the constant 1.
+/// The first `public_witness.len()` wires are public, so after wire 0 they're written as public inputs, and the
+/// rest as private inputs. Circom's distinction between public outputs and inputs isn't tracked here, so there are
+/// never any public outputs. Each wire's label is its index.
+pub fn to_iden3_r1cs<S: PrimeField, W: Write>(r1cs: &R1CS<S>, mut writer: W) -> Result<(), Report> {
+    let field_size = S::MODULUS.to_bytes_le().len();
+    let num_wires = r1cs.L.len();
+    let num_public = r1cs.public_witness.len();
+
+    let mut header = vec![];
+    header.extend((field_size as u32).to_le_bytes());
+    header.extend(S::MODULUS.to_bytes_le());
+    header.extend((num_wires as u32).to_le_bytes());
+    // Public outputs, public inputs (excluding the constant wire 0), private inputs
+    header.extend(0u32.to_le_bytes());
+    header.extend((num_public.saturating_sub(1) as u32).to_le_bytes());
+    header.extend((num_wires.saturating_sub(num_public.max(1)) as u32).to_le_bytes());
+    header.extend((num_wires as u64).to_le_bytes());
+    header.extend((r1cs.num_constraints() as u32).to_le_bytes());
+
+    let mut constraints = vec![];
+    for row in 0..r1cs.num_constraints() {
+        for matrix in [&r1cs.L, &r1cs.R, &r1cs.O] {
+            let terms: Vec<(usize, S)> = matrix
+                .iter()
+                .enumerate()
+                .filter_map(|(wire, column)| {
+                    column
+                        .get(row)
+                        .filter(|x| **x != S::default())
+                        .map(|x| (wire, *x))
+                })
+                .collect();
+            constraints.extend((terms.len() as u32).to_le_bytes());
+            for (wire, coefficient) in terms {
+                constraints.extend((wire as u32).to_le_bytes());
+                constraints.extend(coefficient.into_bigint().to_bytes_le());
+            }
+        }
+    }
+
+    let wire_labels: Vec<u8> = (0..num_wires as u64).flat_map(u64::to_le_bytes).collect();
+
+    let mut bytes = vec![];
+    bytes.extend(MAGIC);
+    bytes.extend(VERSION.to_le_bytes());
+    bytes.extend(3u32.to_le_bytes());
+    for (section, content) in [
+        (HEADER_SECTION, header),
+        (CONSTRAINTS_SECTION, constraints),
+        (WIRE_LABELS_SECTION, wire_labels),
+    ] {
+        bytes.extend(section.to_le_bytes());
+        bytes.extend((content.len() as u64).to_le_bytes());
+        bytes.extend(content);
+    }
+
+    Ok(writer
+        .write_all(&bytes)
+        .context("Writing iden3 r1cs file")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::R1CS;
+    use crate::circuits::export::to_iden3_r1cs;
+    use ark_ff::{BigInteger, PrimeField};
+    use rootcause::Report;
+
+    type Field = ark_mnt6_753::Fr;
+
+    /// Reads the `.r1cs` format back, as a check on the writer rather than a general parser
+    struct Reader<'a>(&'a [u8]);
+
+    impl Reader<'_> {
+        fn take(&mut self, n: usize) -> &[u8] {
+            let (head, tail) = self.0.split_at(n);
+            self.0 = tail;
+            head
+        }
+
+        fn u32(&mut self) -> u32 {
+            u32::from_le_bytes(self.take(4).try_into().unwrap())
+        }
+
+        fn u64(&mut self) -> u64 {
+            u64::from_le_bytes(self.take(8).try_into().unwrap())
+        }
+    }
+
+    #[test]
+    fn iden3_r1cs_export() -> Result<(), Report> {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+
+        let mut bytes = vec![];
+        to_iden3_r1cs(&r1cs, &mut bytes)?;
+        let mut reader = Reader(&bytes);
+
+        assert_eq!(reader.take(4), b"r1cs");
+        assert_eq!(reader.u32(), 1);
+        assert_eq!(reader.u32(), 3);
+
+        assert_eq!(reader.u32(), 1);
+        let header_size = reader.u64() as usize;
+        let field_size = reader.u32() as usize;
+        assert_eq!(field_size, 96);
+        assert_eq!(header_size, 4 + field_size + 4 * 4 + 8 + 4);
+        assert_eq!(reader.take(field_size), Field::MODULUS.to_bytes_le());
+        assert_eq!(reader.u32(), 8); // wires
+        assert_eq!(reader.u32(), 0); // public outputs
+        assert_eq!(reader.u32(), 1); // public inputs
+        assert_eq!(reader.u32(), 6); // private inputs
+        assert_eq!(reader.u64(), 8); // labels
+        assert_eq!(reader.u32(), 3); // constraints
+
+        assert_eq!(reader.u32(), 2);
+        let constraints_size = reader.u64() as usize;
+        let mut constraints = Reader(reader.take(constraints_size));
+        let mut matrices = [
+            vec![vec![Field::from(0); 3]; 8],
+            vec![vec![Field::from(0); 3]; 8],
+            vec![vec![Field::from(0); 3]; 8],
+        ];
+        for row in 0..3 {
+            for matrix in matrices.iter_mut() {
+                for _ in 0..constraints.u32() {
+                    let wire = constraints.u32() as usize;
+                    matrix[wire][row] =
+                        Field::from_le_bytes_mod_order(constraints.take(field_size));
+                }
+            }
+        }
+        assert!(constraints.0.is_empty());
+        let [l, r, o] = matrices;
+        assert_eq!((l, r, o), (r1cs.L, r1cs.R, r1cs.O));
+
+        assert_eq!(reader.u32(), 3);
+        assert_eq!(reader.u64(), 8 * 8);
+        let labels: Vec<u64> = (0..8).map(|_| reader.u64()).collect();
+        assert_eq!(labels, (0..8).collect::<Vec<_>>());
+        assert!(reader.0.is_empty());
+        Ok(())
+    }
+}
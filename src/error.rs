@@ -0,0 +1,154 @@
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use rootcause::Report;
+
+/// The kinds of failure this library can produce. Every `Report` returned by the library has one of these at its
+/// root, with the usual `.context()` chain and attachments built on top.
+/// Use `Groth16Error::find` to pull it back out of a `Report` and branch on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Groth16Error {
+    /// The witness doesn't have one element per QAP variable
+    WitnessLength { expected: usize, got: usize },
+    /// The public witness passed to a verifier doesn't have one element per public input of the circuit
+    PublicWitnessLength { expected: usize, got: usize },
+    /// An SRS doesn't have enough powers of tau to evaluate a polynomial
+    SrsTooSmall { needed: usize, have: usize },
+    /// Polynomial division left a non-zero remainder
+    NonZeroRemainder,
+    /// A witness (or the set of wires it's combined with) was empty
+    EmptyWitness,
+    /// Attempted to divide by the zero polynomial
+    ZeroDivisor,
+    /// The circuit has no constraints
+    EmptyCircuit,
+    /// The circuit has no wires (QAP columns), so there's nothing for a witness to assign
+    NoWires,
+    /// Serialized data couldn't be decoded, e.g. a coordinate that isn't a field element or a point off the curve
+    InvalidEncoding,
+    /// The witness doesn't satisfy the constraint at row `index`, which has `label` if the circuit is labelled
+    UnsatisfiedConstraint { index: usize, label: Option<String> },
+    /// An evaluation domain is too small for the circuit, or the field has no subgroup of the required size
+    InvalidDomain { needed: usize, have: usize },
+    /// The public part of a witness doesn't match the public witness the circuit was set up with
+    PublicWitnessMismatch,
+    /// The public witness has more elements than the circuit has wires
+    PublicWitnessTooLong { public: usize, wires: usize },
+    /// A precomputed `e(alpha, beta)` doesn't match the trusted setup
+    AlphaBetaMismatch,
+    /// The witness solver couldn't determine the value of wire `wire` from the rest of the witness
+    UnsolvedWire { wire: usize },
+    /// A curve name doesn't match any supported curve
+    UnsupportedCurve { name: String },
+    /// A field of a trusted setup doesn't match the rest of it, from `TrustedSetupOutput::sanity_check`
+    InconsistentSetup { field: &'static str },
+    /// A proof doesn't satisfy the verification equation, from `Proof::verify_debug`
+    VerificationFailed,
+    /// The R1CS matrices aren't rectangular, or don't all have the same dimensions
+    InconsistentMatrices,
+    /// An arkworks constraint system is missing its matrices or assignments, e.g. it was synthesized in setup mode
+    IncompleteConstraintSystem,
+    /// The wire map passed to `R1CS::merge` doesn't have one entry per wire of the merged circuit
+    WireMapLength { expected: usize, got: usize },
+    /// A `circuits::Builder` constraint refers to a variable that was never added
+    UnknownVariable { name: String },
+    /// Two variables added to a `circuits::Builder` have the same name
+    DuplicateVariable { name: String },
+    /// A proof element (`A`, `B`, or `C`) is the identity, or isn't a point in the prime order subgroup
+    MalformedProof { element: &'static str },
+}
+
+impl Groth16Error {
+    /// Find the `Groth16Error` at the root of a report, if there is one.
+    pub fn find(report: &Report) -> Option<&Groth16Error> {
+        report
+            .iter_reports()
+            .find_map(|report| report.downcast_current_context::<Groth16Error>())
+    }
+}
+
+impl Display for Groth16Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Groth16Error::WitnessLength { expected, got } => {
+                write!(
+                    f,
+                    "Witness incorrect length! Expected {expected}, got {got}"
+                )
+            }
+            Groth16Error::SrsTooSmall { needed, have } => write!(
+                f,
+                "SRS too small for polynomial, needed {needed} elements but have {have}"
+            ),
+            Groth16Error::NonZeroRemainder => write!(f, "Non zero remainder"),
+            Groth16Error::EmptyWitness => write!(f, "Empty witness"),
+            Groth16Error::NoWires => write!(f, "Circuit has no wires"),
+            Groth16Error::ZeroDivisor => write!(f, "Divisor is zero"),
+            Groth16Error::EmptyCircuit => {
+                write!(f, "Circuit is empty, QAP must have at least one constraint")
+            }
+            Groth16Error::InvalidEncoding => write!(f, "Invalid encoding"),
+            Groth16Error::UnsatisfiedConstraint { index, label } => match label {
+                Some(label) => write!(f, "Constraint {index} ({label}) not satisfied"),
+                None => write!(f, "Constraint {index} not satisfied"),
+            },
+            Groth16Error::PublicWitnessMismatch => {
+                write!(
+                    f,
+                    "Public witness doesn't match the circuit's public witness"
+                )
+            }
+            Groth16Error::PublicWitnessTooLong { public, wires } => write!(
+                f,
+                "Public witness has {public} elements but the circuit only has {wires} wires"
+            ),
+            Groth16Error::AlphaBetaMismatch => {
+                write!(
+                    f,
+                    "Precomputed e(alpha, beta) doesn't match the trusted setup"
+                )
+            }
+            Groth16Error::UnsolvedWire { wire } => {
+                write!(
+                    f,
+                    "Couldn't solve for wire {wire} from the rest of the witness"
+                )
+            }
+            Groth16Error::PublicWitnessLength { expected, got } => write!(
+                f,
+                "Public witness incorrect length! Expected {expected} public inputs, got {got}"
+            ),
+            Groth16Error::InconsistentSetup { field } => {
+                write!(f, "Trusted setup field {field} is inconsistent")
+            }
+            Groth16Error::IncompleteConstraintSystem => {
+                write!(f, "Constraint system is missing its matrices or assignment")
+            }
+            Groth16Error::VerificationFailed => {
+                write!(f, "Proof doesn't satisfy the verification equation")
+            }
+            Groth16Error::InconsistentMatrices => write!(
+                f,
+                "R1CS matrices aren't rectangular with the same dimensions"
+            ),
+            Groth16Error::MalformedProof { element } => write!(
+                f,
+                "Proof element {element} is the identity or not in the prime order subgroup"
+            ),
+            Groth16Error::WireMapLength { expected, got } => write!(
+                f,
+                "Wire map incorrect length! Expected {expected}, got {got}"
+            ),
+            Groth16Error::UnknownVariable { name } => write!(f, "Unknown variable {name}"),
+            Groth16Error::DuplicateVariable { name } => {
+                write!(f, "Variable {name} was added more than once")
+            }
+            Groth16Error::UnsupportedCurve { name } => write!(f, "Unsupported curve {name}"),
+            Groth16Error::InvalidDomain { needed, have } => write!(
+                f,
+                "Invalid evaluation domain, needed at least {needed} points but have {have}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Groth16Error {}
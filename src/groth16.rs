@@ -1,19 +1,36 @@
-use crate::circuits::QAP;
-use crate::helpers::{ark_de, ark_se, rand_scalar};
+use crate::circuits::{QAP, check_domain_size};
+use crate::error::Groth16Error;
+pub use crate::helpers::VerifyResult;
+use crate::helpers::{ark_de, ark_se, ct_eq, from_bytes, rand_scalar, to_bytes};
 use crate::polynomial::Polynomial;
-use ark_ec::PrimeGroup;
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+#[cfg(any(feature = "solidity", feature = "transcript"))]
+use ark_ff::PrimeField;
+use ark_ff::Zero;
 use ark_ff::fields::Field;
-use itertools::izip;
-use log::debug;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use log::{debug, warn};
 use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use rootcause::prelude::ResultExt;
 use rootcause::{Report, bail, report};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "transcript")]
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
 use std::iter::zip;
+#[cfg(feature = "transcript")]
+use std::marker::PhantomData;
+
+mod erased;
+pub use erased::{CurveId, ErasedSetup, dyn_setup};
 
 /// A proof for a witness over some trusted setup. Can be verified with method `verify`.
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+#[derive(
+    Clone, Deserialize, Serialize, Debug, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize,
+)]
 pub struct Proof<C: Pairing> {
     #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     a: C::G1,
@@ -24,40 +41,464 @@ pub struct Proof<C: Pairing> {
 }
 
 impl<C: Pairing> Proof<C> {
+    /// Serialize `A`, `B`, and `C` in arkworks' compressed canonical encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(self)
+    }
+
+    /// Length in bytes of `to_bytes`, without serializing.
+    pub fn serialized_size(&self) -> usize {
+        self.compressed_size()
+    }
+
+    /// Inverse of `to_bytes`. Fails with `Groth16Error::InvalidEncoding` if the bytes don't decode to points in the
+    /// prime order subgroups.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Report> {
+        from_bytes(bytes, "Decoding proof")
+    }
+
+    /// A fresh proof of the same statement, valid whenever this one is, that can't be linked back to it.
+    /// For random `r != 0` and `s` this gives `A' = A / r`, `B' = r * B + r * s * delta` and `C' = C + s * A`, so
+    /// `e(A', B') = e(A, B) + e(s * A, delta)` and the verification equation still holds.
+    /// The result is distributed as a proof freshly made with `prove`, without needing the witness.
+    pub fn rerandomize<R: rand::Rng>(
+        &self,
+        setup: &TrustedSetupOutput<C>,
+        rng: &mut R,
+    ) -> Proof<C> {
+        let (r, r_inverse) = loop {
+            let r: C::ScalarField = rand_scalar(rng);
+            if let Some(r_inverse) = r.inverse() {
+                break (r, r_inverse);
+            }
+        };
+        let s: C::ScalarField = rand_scalar(rng);
+        Proof {
+            a: self.a * r_inverse,
+            b: (self.b + setup.delta_2 * s) * r,
+            c: self.c + self.a * s,
+        }
+    }
+
+    /// Hash the domain separator followed by the compressed canonical bytes of `a`, `b`, and `c` with SHA-256.
+    /// Useful for binding the proof into a transcript for a later protocol phase.
+    #[cfg(feature = "transcript")]
+    pub fn transcript_hash(&self, domain_separator: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update((domain_separator.len() as u64).to_le_bytes());
+        hasher.update(domain_separator);
+
+        let mut bytes = vec![];
+        self.a
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+        self.b
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+        self.c
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+        hasher.update(&bytes);
+
+        hasher.finalize().into()
+    }
+
     /// Verify the proof is valid for a given trusted setup and public witness.
-    /// Returns `true` if the proof is valid, `false` otherwise.
+    /// Returns `Ok(true)` if the proof is valid and `Ok(false)` if it isn't.
+    /// Returns an error if verification couldn't be carried out, e.g. the public witness doesn't have
+    /// `trusted_setup.num_public_inputs()` elements, or if the proof is malformed, with an element that's the identity
+    /// or outside the prime order subgroup.
+    ///
+    /// The four pairings of the verification equation are computed as one multi-pairing, with a single final
+    /// exponentiation. The same pairings are computed whatever the public witness, with the public input term the
+    /// identity when it's empty, so the work done doesn't depend on whether the circuit has public inputs.
+    /// Verification still isn't constant time: arkworks' group and pairing arithmetic isn't, the public input term
+    /// takes longer to compute the longer the public witness, and the final comparison may return early. `verify_ct`
+    /// at least compares in constant time.
     pub fn verify(
         &self,
         trusted_setup: &TrustedSetupOutput<C>,
-        public_witness: &Vec<C::ScalarField>,
+        public_witness: &[C::ScalarField],
+    ) -> Result<bool, Report> {
+        self.verify_with_mode(trusted_setup, public_witness, false)
+    }
+
+    /// Verify the proof as in `verify`, treating any error as an invalid proof.
+    pub fn verify_unwrap(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
     ) -> bool {
+        self.verify(trusted_setup, public_witness)
+            .unwrap_or_else(|e| {
+                warn!("Verification failed with an error: {e}");
+                false
+            })
+    }
+
+    /// Verify the proof as in `verify`, optionally in strict mode.
+    ///
+    /// If the circuit declares public inputs but they are all zero, the public input term is the identity,
+    /// which usually means the caller passed the wrong public witness. This is always logged as a warning,
+    /// and when `strict` is `true` the proof is rejected.
+    pub fn verify_with_mode(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+        strict: bool,
+    ) -> Result<bool, Report> {
         debug!("Verifying with public witness: {:?}", public_witness);
-        let lhs = C::pairing(self.a, self.b);
-        let alpha_beta = C::pairing(trusted_setup.alpha, trusted_setup.beta_2);
-        let x1 = public_witness
+        // `pairing_check` rejects a public witness of the wrong length, even in strict mode
+        let valid = self
+            .pairing_check(trusted_setup, public_witness, None)?
+            .is_zero();
+        if !trusted_setup.qap.public_witness.is_empty()
+            && public_witness
+                .iter()
+                .all(|x| *x == C::ScalarField::default())
+        {
+            warn!("Circuit declares public inputs but they are all zero");
+            if strict {
+                return Ok(false);
+            }
+        }
+
+        Ok(valid)
+    }
+
+    /// Verify the proof as in `verify`, using a precomputed `e(alpha, beta)` rather than recomputing it.
+    /// This lets `alpha_beta` be computed once, e.g. with `TrustedSetupOutput::alpha_beta`, and shared between verifiers.
+    ///
+    /// If `validate` is `true` then `alpha_beta` is checked against the trusted setup first, which costs the pairing
+    /// it would otherwise save, and an error is returned if they don't match.
+    /// Otherwise a wrong `alpha_beta` just causes the proof to be rejected.
+    pub fn verify_with_alpha_beta(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+        alpha_beta: PairingOutput<C>,
+        validate: bool,
+    ) -> Result<bool, Report> {
+        if validate && alpha_beta != trusted_setup.alpha_beta() {
+            return Err(report!(Groth16Error::AlphaBetaMismatch).into());
+        }
+
+        Ok(self
+            .pairing_check(trusted_setup, public_witness, Some(alpha_beta))?
+            .is_zero())
+    }
+
+    /// Verify the proof as in `verify`, but compare the pairing outputs in constant time.
+    /// Callers that branch on the result in timing sensitive contexts should use this rather than `verify`.
+    pub fn verify_ct(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+    ) -> Result<VerifyResult, Report> {
+        let check = self.pairing_check(trusted_setup, public_witness, None)?;
+        let (mut check_bytes, mut identity_bytes) = (vec![], vec![]);
+        check
+            .serialize_uncompressed(&mut check_bytes)
+            .expect("Serializing into a Vec can't fail");
+        PairingOutput::<C>::zero()
+            .serialize_uncompressed(&mut identity_bytes)
+            .expect("Serializing into a Vec can't fail");
+        Ok(VerifyResult::from_ct_eq(ct_eq(
+            &check_bytes,
+            &identity_bytes,
+        )))
+    }
+
+    /// Verify the proof as in `verify`, using just the verifying key rather than the whole trusted setup.
+    pub fn verify_with_key(
+        &self,
+        verifying_key: &VerifyingKeyAffine<C>,
+        public_witness: &[C::ScalarField],
+    ) -> Result<bool, Report> {
+        self.check_elements()
+            .context("Proof failed sanity checks")?;
+        check_public_witness_length(verifying_key.num_public_inputs(), public_witness)?;
+        let psi_polynomials: Vec<C::G1> = verifying_key
+            .psi_polynomials
             .iter()
-            .enumerate()
-            .map(|(i, a_i)| trusted_setup.psi_polynomials[i] * a_i)
-            .reduce(|a, b| a + b);
-        let x1_gamma = if let Some(x1) = x1 {
-            Some(C::pairing(x1, trusted_setup.gamma))
-        } else {
-            None
-        };
-        let c_delta = C::pairing(self.c, trusted_setup.delta_2);
+            .map(|x| x.into_group())
+            .collect();
+        let x1 = public_input_commitment(&psi_polynomials, public_witness)
+            .context("Failed to compute public input term")?;
+        Ok(self
+            .multi_pairing_check(
+                Some((
+                    verifying_key.alpha.into_group(),
+                    verifying_key.beta_2.into_group(),
+                )),
+                x1,
+                verifying_key.gamma.into_group(),
+                verifying_key.delta_2.into_group(),
+            )
+            .is_zero())
+    }
 
-        debug!("{} == {} + {:?} + {}", lhs, alpha_beta, x1_gamma, c_delta);
-        let rhs = if let Some(x1_gamma) = x1_gamma {
-            alpha_beta + x1_gamma + c_delta
-        } else {
-            alpha_beta + c_delta
+    /// Check none of `A`, `B`, and `C` are the identity, and that each is on the curve and in the prime order
+    /// subgroup, failing with `Groth16Error::MalformedProof` naming the first that isn't.
+    /// Every `verify` method does this before any pairings.
+    fn check_elements(&self) -> Result<(), Report> {
+        for (element, is_identity, valid) in [
+            ("A", self.a == C::G1::default(), self.a.check()),
+            ("B", self.b == C::G2::default(), self.b.check()),
+            ("C", self.c == C::G1::default(), self.c.check()),
+        ] {
+            if is_identity {
+                return Err(report!(Groth16Error::MalformedProof { element })
+                    .attach("Element is the identity")
+                    .into());
+            }
+            if let Err(e) = valid {
+                return Err(report!(Groth16Error::MalformedProof { element })
+                    .attach(format!("{e}"))
+                    .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// The verification equation `e(A, B) == e(alpha, beta) + e(x, gamma) + e(C, delta)` rearranged as
+    /// `e(A, B) - e(alpha, beta) - e(x, gamma) - e(C, delta)`, which is the identity exactly when the proof is valid.
+    /// `e(alpha, beta)` is part of the multi-pairing unless `alpha_beta` is given precomputed.
+    fn pairing_check(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+        alpha_beta: Option<PairingOutput<C>>,
+    ) -> Result<PairingOutput<C>, Report> {
+        self.check_elements()
+            .context("Proof failed sanity checks")?;
+        check_public_witness_length(trusted_setup.num_public_inputs(), public_witness)?;
+        let x1 = trusted_setup
+            .public_input_commitment(public_witness)
+            .context("Failed to compute public input term")?;
+        let gamma = trusted_setup.gamma;
+        let delta_2 = trusted_setup.delta_2;
+        Ok(match alpha_beta {
+            Some(alpha_beta) => self.multi_pairing_check(None, x1, gamma, delta_2) - alpha_beta,
+            None => self.multi_pairing_check(
+                Some((trusted_setup.alpha, trusted_setup.beta_2)),
+                x1,
+                gamma,
+                delta_2,
+            ),
+        })
+    }
+
+    /// `e(A, B) - e(alpha, beta) - e(x1, gamma) - e(C, delta)` as a single multi-pairing, so the Miller loops share one
+    /// final exponentiation rather than paying for one per pairing. `e(alpha, beta)` is left out if `alpha_beta` is
+    /// `None`, for callers that have it precomputed.
+    fn multi_pairing_check(
+        &self,
+        alpha_beta: Option<(C::G1, C::G2)>,
+        x1: C::G1,
+        gamma: C::G2,
+        delta_2: C::G2,
+    ) -> PairingOutput<C> {
+        let mut g1 = vec![self.a, -x1, -self.c];
+        let mut g2 = vec![self.b, gamma, delta_2];
+        if let Some((alpha, beta_2)) = alpha_beta {
+            g1.push(-alpha);
+            g2.push(beta_2);
+        }
+        C::multi_pairing(g1, g2)
+    }
+
+    /// Each pairing in the verification equation computed separately, for `verify_debug` to report.
+    fn pairing_terms(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+        alpha_beta: Option<PairingOutput<C>>,
+    ) -> Result<PairingTerms<C>, Report> {
+        self.check_elements()
+            .context("Proof failed sanity checks")?;
+        check_public_witness_length(trusted_setup.num_public_inputs(), public_witness)?;
+        let alpha_beta = alpha_beta.unwrap_or_else(|| trusted_setup.alpha_beta());
+        let x1 = trusted_setup
+            .public_input_commitment(public_witness)
+            .context("Failed to compute public input term")?;
+        Ok(self.equation_terms(alpha_beta, x1, trusted_setup.gamma, trusted_setup.delta_2))
+    }
+
+    /// Each pairing in the verification equation, given `e(alpha, beta)` and the public input term `x1`.
+    fn equation_terms(
+        &self,
+        alpha_beta: PairingOutput<C>,
+        x1: C::G1,
+        gamma: C::G2,
+        delta_2: C::G2,
+    ) -> PairingTerms<C> {
+        let terms = PairingTerms {
+            a_b: C::pairing(self.a, self.b),
+            alpha_beta,
+            x1_gamma: C::pairing(x1, gamma),
+            c_delta: C::pairing(self.c, delta_2),
         };
-        lhs.0 == rhs.0
+        debug!(
+            "{} == {} + {} + {}",
+            terms.a_b, terms.alpha_beta, terms.x1_gamma, terms.c_delta
+        );
+        terms
+    }
+
+    /// Verify the proof as in `verify`, but rather than returning `Ok(false)` when the proof is rejected, fail with
+    /// `Groth16Error::VerificationFailed`, attaching both sides of the verification equation along with each of the
+    /// pairings that make them up, to see how a broken circuit's proof is off.
+    pub fn verify_debug(
+        &self,
+        trusted_setup: &TrustedSetupOutput<C>,
+        public_witness: &[C::ScalarField],
+    ) -> Result<bool, Report> {
+        let terms = self.pairing_terms(trusted_setup, public_witness, None)?;
+        let (lhs, rhs) = terms.sides();
+        if lhs != rhs {
+            return Err(report!(Groth16Error::VerificationFailed)
+                .attach(format!("LHS: {lhs}"))
+                .attach(format!("RHS: {rhs}"))
+                .attach(format!("e(A, B): {}", terms.a_b))
+                .attach(format!("e(alpha, beta): {}", terms.alpha_beta))
+                .attach(format!("e(x, gamma): {}", terms.x1_gamma))
+                .attach(format!("e(C, delta): {}", terms.c_delta))
+                .into());
+        }
+        Ok(true)
+    }
+}
+
+/// The pairings making up the verification equation `e(A, B) == e(alpha, beta) + e(x, gamma) + e(C, delta)`,
+/// where `e(x, gamma)` is the identity if there's no public witness.
+struct PairingTerms<C: Pairing> {
+    a_b: PairingOutput<C>,
+    alpha_beta: PairingOutput<C>,
+    x1_gamma: PairingOutput<C>,
+    c_delta: PairingOutput<C>,
+}
+
+impl<C: Pairing> PairingTerms<C> {
+    /// The left and right hand sides of the equation.
+    fn sides(&self) -> (C::TargetField, C::TargetField) {
+        (
+            self.a_b.0,
+            (self.alpha_beta + self.x1_gamma + self.c_delta).0,
+        )
+    }
+}
+
+/// Fail with `Groth16Error::PublicWitnessLength` unless the public witness has `expected` elements.
+fn check_public_witness_length<F>(expected: usize, public_witness: &[F]) -> Result<(), Report> {
+    if public_witness.len() != expected {
+        bail!(Groth16Error::PublicWitnessLength {
+            expected,
+            got: public_witness.len()
+        });
+    }
+    Ok(())
+}
+
+/// Combine a public witness with the public psi polynomials, i.e. `sum psi_i(tau)/gamma * a_i`.
+fn public_input_commitment<G: CurveGroup>(
+    psi_polynomials: &[G],
+    public_witness: &[G::ScalarField],
+) -> Result<G, Report> {
+    if public_witness.len() > psi_polynomials.len() {
+        return Err(report!(Groth16Error::SrsTooSmall {
+            needed: public_witness.len(),
+            have: psi_polynomials.len(),
+        })
+        .attach("Trusted setup has fewer psi polynomials than the public witness")
+        .into());
+    }
+    Ok(zip(public_witness, psi_polynomials)
+        .map(|(a_i, psi_i)| *psi_i * a_i)
+        .sum())
+}
+
+/// The parts of a trusted setup needed to verify proofs, as affine points. Can be verified against with
+/// `Proof::verify_with_key`.
+///
+/// Serializes with arkworks' canonical encoding, so round-trips through `CanonicalSerialize` in compressed or
+/// uncompressed form. Field elements are written as little-endian bytes, and extension field elements as each of their
+/// coefficients in turn from `c0` up. Uncompressed points are `x` then `y`, compressed points are just `x` with the
+/// sign of `y` and whether the point is the identity stored as flags in the top bits of the last byte.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKeyAffine<C: Pairing> {
+    pub alpha: C::G1Affine,
+    pub beta_2: C::G2Affine,
+    pub gamma: C::G2Affine,
+    pub delta_2: C::G2Affine,
+    /// The psi polynomials for the public part of the witness, `psi_i(tau)/gamma`
+    pub psi_polynomials: Vec<C::G1Affine>,
+}
+
+impl<C: Pairing> VerifyingKeyAffine<C> {
+    /// Serialize in arkworks' compressed canonical encoding, as described on the type.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(self)
+    }
+
+    /// Inverse of `to_bytes`. Fails with `Groth16Error::InvalidEncoding` if the bytes don't decode.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Report> {
+        from_bytes(bytes, "Decoding verifying key")
+    }
+
+    /// The number of public inputs, i.e. the length of the public witness a proof is verified against.
+    pub fn num_public_inputs(&self) -> usize {
+        self.psi_polynomials.len()
+    }
+}
+
+/// Tuning options for `TrustedSetupOutput::new_with_config`.
+/// Apart from `zero_polynomial_srs_2` these only change how quickly the setup is computed, the setup itself is the
+/// same for any config.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SetupConfig {
+    /// Window size in bits for evaluating polynomials over the SRS, see `Polynomial::evaluate_over_srs_with_window`.
+    /// If `None` it's picked from the size of each polynomial.
+    pub window: Option<usize>,
+    /// Number of threads to run the setup on. Only used with the `parallel` feature, if `None` rayon's global
+    /// thread pool is used.
+    pub threads: Option<usize>,
+    /// Convert the group 1 SRS to affine form once before evaluating polynomials over it, which makes each
+    /// evaluation cheaper at the cost of holding a second copy of the SRS.
+    pub precompute_affine: bool,
+    /// Also build the zero polynomial SRS in group 2, for experimenting with arrangements of the pairing that commit
+    /// to `h(x)t(x)` in G2. See `TrustedSetupOutput::zero_polynomial_srs_2`.
+    pub zero_polynomial_srs_2: bool,
+}
+
+/// An SRS along with how to evaluate polynomials over it, as chosen by a `SetupConfig`.
+struct ConfiguredSrs<'a, G: CurveGroup> {
+    srs: &'a [G],
+    affine: Option<Vec<G::Affine>>,
+    window: Option<usize>,
+}
+
+impl<'a, G: CurveGroup> ConfiguredSrs<'a, G> {
+    fn new(srs: &'a [G], config: &SetupConfig) -> Self {
+        ConfiguredSrs {
+            srs,
+            affine: config.precompute_affine.then(|| G::normalize_batch(srs)),
+            window: config.window,
+        }
+    }
+
+    fn evaluate(&self, polynomial: &Polynomial<G::ScalarField>) -> Result<G, Report> {
+        match &self.affine {
+            Some(affine) => polynomial.evaluate_over_affine_srs(affine, self.window),
+            None => polynomial.evaluate_over_srs_with_window(self.srs, self.window),
+        }
     }
 }
 
 /// A trusted setup for a given QAP. Should be instantiated once and shared in serialized form to all parties that wish to generate proofs for the QAP.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(
+    Clone, Serialize, Deserialize, Debug, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize,
+)]
 pub struct TrustedSetupOutput<C: Pairing> {
     #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     qap: QAP<C::ScalarField>,
@@ -79,123 +520,540 @@ pub struct TrustedSetupOutput<C: Pairing> {
     group_2_srs: Vec<C::G2>,
     #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     zero_polynomial_srs: Vec<C::G1>,
+    /// The zero polynomial SRS in G2, empty unless requested by `SetupConfig::zero_polynomial_srs_2`
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    zero_polynomial_srs_2: Vec<C::G2>,
     #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
     psi_polynomials: Vec<C::G1>,
+    /// u_i(tau) in G1 for each QAP column, cached so proving is just a witness-weighted sum
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    u_tau: Vec<C::G1>,
+    /// v_i(tau) in G1
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    v_tau_1: Vec<C::G1>,
+    /// v_i(tau) in G2
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    v_tau_2: Vec<C::G2>,
 }
 
 impl<C: Pairing> TrustedSetupOutput<C> {
-    fn group_1_srs(length: usize, tau: C::ScalarField) -> Vec<C::G1> {
+    /// Serialize every field in arkworks' compressed canonical encoding, which is much smaller than the JSON form.
+    /// `serialize_deduplicated` can be smaller still when the setup has repeated elements.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(self)
+    }
+
+    /// Inverse of `to_bytes`. Fails with `Groth16Error::InvalidEncoding` if the bytes don't decode.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Report> {
+        from_bytes(bytes, "Decoding trusted setup")
+    }
+
+    /// The QAP this setup was run for.
+    pub fn qap(&self) -> &QAP<C::ScalarField> {
+        &self.qap
+    }
+
+    /// `alpha` in G1.
+    pub fn alpha(&self) -> &C::G1 {
+        &self.alpha
+    }
+
+    /// The number of public inputs, i.e. the length of the public witness a proof is verified against.
+    pub fn num_public_inputs(&self) -> usize {
+        self.qap.public_witness.len()
+    }
+
+    /// `e(alpha, beta)`, the constant term of the verification equation.
+    /// It only depends on the trusted setup so can be computed once and passed to `Proof::verify_with_alpha_beta`.
+    pub fn alpha_beta(&self) -> PairingOutput<C> {
+        C::pairing(self.alpha, self.beta_2)
+    }
+
+    /// `beta` in G1.
+    pub fn beta_1(&self) -> &C::G1 {
+        &self.beta_1
+    }
+
+    /// `beta` in G2.
+    pub fn beta_2(&self) -> &C::G2 {
+        &self.beta_2
+    }
+
+    /// `gamma` in G2.
+    pub fn gamma(&self) -> &C::G2 {
+        &self.gamma
+    }
+
+    /// `delta` in G1.
+    pub fn delta_1(&self) -> &C::G1 {
+        &self.delta_1
+    }
+
+    /// `delta` in G2.
+    pub fn delta_2(&self) -> &C::G2 {
+        &self.delta_2
+    }
+
+    /// Powers of tau in G1, `[G1, tau * G1, tau^2 * G1, ...]`.
+    pub fn group_1_srs(&self) -> &[C::G1] {
+        &self.group_1_srs
+    }
+
+    /// Powers of tau in G2, `[G2, tau * G2, tau^2 * G2, ...]`.
+    pub fn group_2_srs(&self) -> &[C::G2] {
+        &self.group_2_srs
+    }
+
+    /// `[t(tau)/delta, tau * t(tau)/delta, ...]` in G1, for committing to `h(x)t(x)`.
+    pub fn zero_polynomial_srs(&self) -> &[C::G1] {
+        &self.zero_polynomial_srs
+    }
+
+    /// `zero_polynomial_srs` in G2. Empty unless the setup was run with `SetupConfig::zero_polynomial_srs_2`.
+    pub fn zero_polynomial_srs_2(&self) -> &[C::G2] {
+        &self.zero_polynomial_srs_2
+    }
+
+    /// `(beta * u_i(tau) + alpha * v_i(tau) + w_i(tau))` in G1, divided by gamma for the public part of the witness
+    /// and delta for the private part.
+    pub fn psi_polynomials(&self) -> &[C::G1] {
+        &self.psi_polynomials
+    }
+
+    /// Combine the public witness with the public part of the psi polynomials, i.e. `sum psi_i(tau)/gamma * a_i`.
+    /// This is the public input term that `Proof::verify` pairs with gamma, exposed so it can be checked on its own.
+    /// Returns an error if there are fewer psi polynomials than public witness elements.
+    pub fn public_input_commitment(
+        &self,
+        public_witness: &[C::ScalarField],
+    ) -> Result<C::G1, Report> {
+        public_input_commitment(&self.psi_polynomials, public_witness)
+    }
+
+    /// The verifying key, i.e. `alpha`, `beta_2`, `gamma`, `delta_2`, and the public psi polynomials, as affine points.
+    pub fn verifying_key_affine(&self) -> VerifyingKeyAffine<C> {
+        let public = self
+            .qap
+            .public_witness
+            .len()
+            .min(self.psi_polynomials.len());
+        VerifyingKeyAffine {
+            alpha: self.alpha.into_affine(),
+            beta_2: self.beta_2.into_affine(),
+            gamma: self.gamma.into_affine(),
+            delta_2: self.delta_2.into_affine(),
+            psi_polynomials: C::G1::normalize_batch(&self.psi_polynomials[..public]),
+        }
+    }
+
+    /// Check the setup is internally consistent, catching e.g. a corrupted deserialization or a broken custom curve.
+    /// Fails with `Groth16Error::InconsistentSetup` naming the first field that doesn't match the rest, if:
+    ///
+    /// - either SRS doesn't start at the generator of its group
+    /// - the SRSs, psi polynomials, or cached QAP evaluations aren't the length the QAP calls for
+    /// - the two SRSs aren't successive powers of the same `tau`
+    /// - `beta` or `delta` differ between the two groups
+    /// - the cached QAP evaluations aren't the QAP polynomials evaluated over the SRS
+    ///
+    /// Sequences are checked with a random linear combination, so a bad element slips through with negligible
+    /// probability. Nothing divided by `gamma` or `delta` can be checked without knowing them.
+    pub fn sanity_check(&self) -> Result<(), Report> {
+        let inconsistent = |field: &'static str, reason: &str| -> Result<(), Report> {
+            Err(report!(Groth16Error::InconsistentSetup { field })
+                .attach(reason.to_string())
+                .into())
+        };
+
+        let degree = self.qap.degree();
+        // A setup can't be run for an empty domain, so this only happens to a corrupted one
+        let Some(degree_minus_one) = degree.checked_sub(1) else {
+            return inconsistent("qap", "QAP has an empty domain");
+        };
+        let wires = self.qap.u.len();
+        for (field, len, expected) in [
+            (
+                "group_1_srs",
+                self.group_1_srs.len(),
+                degree + degree_minus_one,
+            ),
+            ("group_2_srs", self.group_2_srs.len(), degree),
+            (
+                "zero_polynomial_srs",
+                self.zero_polynomial_srs.len(),
+                degree_minus_one,
+            ),
+            (
+                "zero_polynomial_srs_2",
+                self.zero_polynomial_srs_2.len(),
+                // Only present if requested
+                if self.zero_polynomial_srs_2.is_empty() {
+                    0
+                } else {
+                    degree_minus_one
+                },
+            ),
+            ("psi_polynomials", self.psi_polynomials.len(), wires),
+            ("u_tau", self.u_tau.len(), wires),
+            ("v_tau_1", self.v_tau_1.len(), self.qap.v.len()),
+            ("v_tau_2", self.v_tau_2.len(), self.qap.v.len()),
+        ] {
+            if len != expected {
+                return inconsistent(field, &format!("Length {len}, expected {expected}"));
+            }
+        }
+
+        if self.group_1_srs[0] != C::G1::generator() {
+            return inconsistent("group_1_srs", "First element isn't the generator");
+        }
+        if self.group_2_srs[0] != C::G2::generator() {
+            return inconsistent("group_2_srs", "First element isn't the generator");
+        }
+        if C::pairing(self.beta_1, C::G2::generator())
+            != C::pairing(C::G1::generator(), self.beta_2)
+        {
+            return inconsistent("beta_2", "Differs from beta_1");
+        }
+        if C::pairing(self.delta_1, C::G2::generator())
+            != C::pairing(C::G1::generator(), self.delta_2)
+        {
+            return inconsistent("delta_2", "Differs from delta_1");
+        }
+
+        let mut rng = rand::rng();
+        if let Some(tau_2) = self.group_2_srs.get(1) {
+            // e(sum r_i tau^i, tau) == e(sum r_i tau^(i+1), 1)
+            let r: Vec<C::ScalarField> = (1..self.group_1_srs.len())
+                .map(|_| rand_scalar(&mut rng))
+                .collect();
+            let lower: C::G1 = zip(&self.group_1_srs, &r).map(|(x, r_i)| *x * r_i).sum();
+            let upper: C::G1 = zip(&self.group_1_srs[1..], &r)
+                .map(|(x, r_i)| *x * r_i)
+                .sum();
+            if C::pairing(lower, *tau_2) != C::pairing(upper, C::G2::generator()) {
+                return inconsistent("group_1_srs", "Not successive powers of group 2's tau");
+            }
+            let lower: C::G2 = zip(&self.group_2_srs[..self.group_2_srs.len() - 1], &r)
+                .map(|(x, r_i)| *x * r_i)
+                .sum();
+            let upper: C::G2 = zip(&self.group_2_srs[1..], &r)
+                .map(|(x, r_i)| *x * r_i)
+                .sum();
+            if C::pairing(self.group_1_srs[1], lower) != C::pairing(C::G1::generator(), upper) {
+                return inconsistent("group_2_srs", "Not successive powers of group 1's tau");
+            }
+        }
+
+        // sum r_i [p_i(tau)] == [(sum r_i p_i)(tau)]
+        let r: Vec<C::ScalarField> = (0..wires).map(|_| rand_scalar(&mut rng)).collect();
+        let combine = |polynomials: &[Polynomial<C::ScalarField>]| -> Polynomial<C::ScalarField> {
+            zip(polynomials, &r).map(|(p, r_i)| p * *r_i).sum()
+        };
+        for (field, cached, polynomials) in [
+            ("u_tau", &self.u_tau, &self.qap.u),
+            ("v_tau_1", &self.v_tau_1, &self.qap.v),
+        ] {
+            let expected = combine(polynomials)
+                .evaluate_over_srs(&self.group_1_srs)
+                .context("Evaluating combined QAP polynomials")?;
+            if zip(cached, &r).map(|(x, r_i)| *x * r_i).sum::<C::G1>() != expected {
+                return inconsistent(field, "Doesn't match the QAP evaluated over the SRS");
+            }
+        }
+        let expected = combine(&self.qap.v)
+            .evaluate_over_srs(&self.group_2_srs)
+            .context("Evaluating combined QAP polynomials")?;
+        if zip(&self.v_tau_2, &r)
+            .map(|(x, r_i)| *x * r_i)
+            .sum::<C::G2>()
+            != expected
+        {
+            return inconsistent("v_tau_2", "Doesn't match the QAP evaluated over the SRS");
+        }
+
+        // e(sum r_i [x^i t(tau)/delta]_1, 1) == e(1, sum r_i [x^i t(tau)/delta]_2)
+        if !self.zero_polynomial_srs_2.is_empty() {
+            let r: Vec<C::ScalarField> = (0..self.zero_polynomial_srs.len())
+                .map(|_| rand_scalar(&mut rng))
+                .collect();
+            let group_1: C::G1 = zip(&self.zero_polynomial_srs, &r)
+                .map(|(x, r_i)| *x * r_i)
+                .sum();
+            let group_2: C::G2 = zip(&self.zero_polynomial_srs_2, &r)
+                .map(|(x, r_i)| *x * r_i)
+                .sum();
+            if C::pairing(group_1, C::G2::generator()) != C::pairing(C::G1::generator(), group_2) {
+                return inconsistent("zero_polynomial_srs_2", "Differs from zero_polynomial_srs");
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_group_1_srs(length: usize, tau: C::ScalarField) -> Vec<C::G1> {
         (0..length)
             .map(|i| C::G1::generator() * tau.pow([i as u64]))
             .collect()
     }
 
-    /// Get zero polynomial (x - 1)(x -2)(...)(x - n)
-    fn t(num_roots: usize) -> Result<Polynomial<C::ScalarField>, Report> {
-        Ok((1..num_roots + 1)
-            .map(|x| {
-                Polynomial::new(vec![
-                    -C::ScalarField::from(x as u128),
-                    C::ScalarField::from(1),
-                ])
-            })
-            .reduce(std::ops::Mul::mul)
-            .ok_or(report!("QAP has degree zero"))?)
+    fn generate_group_2_srs(length: usize, tau: C::ScalarField) -> Vec<C::G2> {
+        (0..length)
+            .map(|i| C::G2::generator() * tau.pow([i as u64]))
+            .collect()
+    }
+
+    /// Get zero polynomial (x - d_1)(x - d_2)(...)(x - d_n) over the QAP's evaluation domain.
+    /// For the default domain `1, 2, ..., n` this is (x - 1)(x -2)(...)(x - n), for a roots of unity domain it's x^n - 1.
+    /// Fails with `Groth16Error::InvalidDomain` if the field is too small for the domain's points to be distinct.
+    fn t(domain: &[C::ScalarField]) -> Result<Polynomial<C::ScalarField>, Report> {
+        if domain.is_empty() {
+            return Err(report!(Groth16Error::EmptyCircuit)
+                .attach("QAP has degree zero")
+                .into());
+        }
+        check_domain_size::<C::ScalarField>(domain.len())?;
+        Ok(Polynomial::vanishing(domain))
     }
 
     /// Generate SRS for the zero polynomial of form [t(tau)/delta, tau * t(tau)/delta, tau^2 * t(tau)/delta, ...]
     ///
     /// # Arguments
     ///
-    /// * `num_evaluation_points`: Roots of zero polynomial, i.e. `[1,2,...].len()`, i.e. the degree of the QAP
+    /// * `t`: Zero polynomial over the QAP's evaluation domain, from `t`, built once per setup and shared between groups
     /// * `srs_length`: The length of the SRS is 1 more than the degree of polynomial it needs to support
     /// * `delta`: Secret scalar used to ensure separation of public/private witness
-    /// * `group_1_srs`: SRS for G1
+    /// * `srs`: SRS for the group to build it in, prepared as set by the setup config
     ///
-    /// returns: Result<Vec<<C as Pairing>::G1, Global>, Report<dyn Any, Mutable, SendSync>>
-    fn zero_polynomial_srs(
-        num_evaluation_points: usize,
+    /// returns: Result<Vec<G, Global>, Report<dyn Any, Mutable, SendSync>>
+    fn generate_zero_polynomial_srs<G>(
+        t: &Polynomial<C::ScalarField>,
         srs_length: usize,
         delta: C::ScalarField,
-        group_1_srs: &Vec<C::G1>,
-    ) -> Result<Vec<C::G1>, Report> {
-        let t_tau = Self::t(num_evaluation_points)?;
-
-        debug!("Generated t(tau)");
-
-        Ok((0..srs_length)
+        srs: &ConfiguredSrs<G>,
+    ) -> Result<Vec<G>, Report>
+    where
+        G: CurveGroup<ScalarField = C::ScalarField>,
+    {
+        let t_over_delta = t / delta;
+
+        debug!("Generated t(x)/delta");
+
+        let indices = 0..srs_length;
+        #[cfg(feature = "parallel")]
+        let indices = indices.into_par_iter();
+        Ok(indices
             .map(|i| {
-                let mut Zx = vec![0; i + 1];
-                Zx[i] = 1;
-                let poly = Polynomial::from(Zx);
-                (&(&t_tau * &poly) / delta)
-                    .evaluate_over_srs(&group_1_srs)
-                    .context("Evaluating over group_1_srs")
-                    .attach(format!("t_tau: {:?}", t_tau))
-                    .attach(format!("poly: {:?}", poly))
+                srs.evaluate(&t_over_delta.shift(i))
+                    .context("Evaluating over SRS")
+                    .attach(format!("t(x)/delta: {:?}", t_over_delta))
+                    .attach(format!("shift: {i}"))
             })
             .collect::<Result<_, _>>()?)
     }
 
-    fn psi_polynomials(
+    fn generate_psi_polynomials(
         qap: &QAP<C::ScalarField>,
-        group_1_srs: &Vec<C::G1>,
+        group_1_srs: &ConfiguredSrs<C::G1>,
         alpha: C::ScalarField,
         beta: C::ScalarField,
         gamma: C::ScalarField,
         delta: C::ScalarField,
     ) -> Result<Vec<C::G1>, Report> {
-        izip!(&qap.u, &qap.v, &qap.w)
-            .enumerate()
-            .map(|(i, (u_i, v_i, w_i))| {
+        let indices = 0..qap.u.len().min(qap.v.len()).min(qap.w.len());
+        #[cfg(feature = "parallel")]
+        let indices = indices.into_par_iter();
+        indices
+            .map(|i| {
                 let divisor = if i < qap.public_witness.len() {
                     gamma
                 } else {
                     delta
                 };
 
-                let numerator_poly = &((v_i * alpha) + (u_i * beta)) + w_i;
-                Ok(numerator_poly.evaluate_over_srs(&group_1_srs)?
-                    * (C::ScalarField::from(1) / divisor))
+                let numerator_poly = &((&qap.v[i] * alpha) + (&qap.u[i] * beta)) + &qap.w[i];
+                Ok(group_1_srs.evaluate(&numerator_poly)? * (C::ScalarField::from(1) / divisor))
             })
             .collect::<Result<Vec<_>, Report>>()
     }
 
     /// Run a new trusted setup for a given QAP.
+    ///
+    /// With the `zeroize` feature the secret scalars are wiped from memory when this returns. This is best-effort only,
+    /// copies passed on to `new_with_secrets` aren't wiped, and the group elements derived from them remain.
     pub fn new(qap: QAP<C::ScalarField>) -> Result<TrustedSetupOutput<C>, Report> {
+        Self::new_with_config(qap, &SetupConfig::default())
+    }
+
+    /// Run a new trusted setup for a given QAP as in `new`, tuned by `config`.
+    pub fn new_with_config(
+        qap: QAP<C::ScalarField>,
+        config: &SetupConfig,
+    ) -> Result<TrustedSetupOutput<C>, Report> {
         debug!("Starting trusted setup");
         let mut rng = rand::rngs::StdRng::from_os_rng();
         debug!("Got RNG");
 
-        let alpha: C::ScalarField = rand_scalar(&mut rng);
-        let beta: C::ScalarField = rand_scalar(&mut rng);
-        let tau: C::ScalarField = rand_scalar(&mut rng);
-        let gamma: C::ScalarField = rand_scalar(&mut rng);
-        let delta: C::ScalarField = rand_scalar(&mut rng);
+        // alpha, beta, tau, gamma, delta
+        let secrets: [C::ScalarField; 5] = std::array::from_fn(|_| rand_scalar(&mut rng));
+        #[cfg(feature = "zeroize")]
+        let secrets = zeroize::Zeroizing::new(secrets);
 
         debug!("Generated random scalars");
 
-        let group_1_srs = Self::group_1_srs((2 * qap.degree()) - 1, tau);
+        let setup = || {
+            Self::new_with_secrets_and_config(
+                qap, secrets[0], secrets[1], secrets[2], secrets[3], secrets[4], config,
+            )
+        };
+        #[cfg(feature = "parallel")]
+        if let Some(threads) = config.threads {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .context("Building thread pool for setup")?;
+            return pool.install(setup);
+        }
+        setup()
+    }
+
+    /// Run a trusted setup for a given QAP with the secret scalars drawn from `StdRng` seeded by `seed`, so the same
+    /// seed always gives the same setup, e.g. for snapshotting a setup in a test or sharing a known-answer vector.
+    ///
+    /// **Insecure**: anyone who knows the seed knows the secrets, so can forge proofs. Never use this for a setup
+    /// that proofs will be trusted against.
+    pub fn new_seeded(
+        qap: QAP<C::ScalarField>,
+        seed: [u8; 32],
+    ) -> Result<TrustedSetupOutput<C>, Report> {
+        let mut rng = rand::rngs::StdRng::from_seed(seed);
+        // alpha, beta, tau, gamma, delta
+        let secrets: [C::ScalarField; 5] = std::array::from_fn(|_| rand_scalar(&mut rng));
+        #[cfg(feature = "zeroize")]
+        let secrets = zeroize::Zeroizing::new(secrets);
+
+        Self::new_with_secrets(
+            qap, secrets[0], secrets[1], secrets[2], secrets[3], secrets[4],
+        )
+    }
+
+    /// Run a trusted setup for a given QAP using externally supplied secret scalars (the "toxic waste").
+    /// Anyone who knows these scalars can forge proofs, so this is only intended for reproducible tests
+    /// and for experimenting with setup ceremonies.
+    pub fn new_with_secrets(
+        qap: QAP<C::ScalarField>,
+        alpha: C::ScalarField,
+        beta: C::ScalarField,
+        tau: C::ScalarField,
+        gamma: C::ScalarField,
+        delta: C::ScalarField,
+    ) -> Result<TrustedSetupOutput<C>, Report> {
+        Self::new_with_secrets_and_config(
+            qap,
+            alpha,
+            beta,
+            tau,
+            gamma,
+            delta,
+            &SetupConfig::default(),
+        )
+    }
+
+    fn new_with_secrets_and_config(
+        qap: QAP<C::ScalarField>,
+        alpha: C::ScalarField,
+        beta: C::ScalarField,
+        tau: C::ScalarField,
+        gamma: C::ScalarField,
+        delta: C::ScalarField,
+        config: &SetupConfig,
+    ) -> Result<TrustedSetupOutput<C>, Report> {
+        if qap.degree() == 0 {
+            bail!(Groth16Error::EmptyCircuit);
+        }
+        // Otherwise every psi polynomial would be divided by gamma, and the whole witness would be public
+        if qap.public_witness.len() > qap.u.len() {
+            return Err(report!(Groth16Error::PublicWitnessTooLong {
+                public: qap.public_witness.len(),
+                wires: qap.u.len(),
+            })
+            .context("Checking public witness fits in the circuit")
+            .into());
+        }
+
+        let group_1_srs_length = (2 * qap.degree())
+            .checked_sub(1)
+            .ok_or(report!(Groth16Error::EmptyCircuit))
+            .attach(format!("QAP degree: {}", qap.degree()))
+            .context("Calculating group 1 SRS length")?;
+        // h(x) has degree at most n - 2, so needs an SRS of length n - 1. For a single constraint this is empty.
+        let zero_polynomial_srs_length = qap
+            .degree()
+            .checked_sub(1)
+            .ok_or(report!(Groth16Error::EmptyCircuit))
+            .attach(format!("QAP degree: {}", qap.degree()))
+            .context("Calculating zero polynomial SRS length")?;
+        Self::check_srs_lengths(
+            &qap,
+            group_1_srs_length,
+            qap.degree(),
+            zero_polynomial_srs_length,
+        )
+        .context("Checking SRS lengths cover the QAP")?;
+
+        let group_1_srs = Self::generate_group_1_srs(group_1_srs_length, tau);
+        let configured_group_1_srs = ConfiguredSrs::new(&group_1_srs, config);
 
         debug!("Generated Group 1 SRS");
 
-        let group_2_srs = (0..qap.degree())
-            .map(|i| C::G2::generator() * tau.pow([i as u64]))
-            .collect();
+        let group_2_srs = Self::generate_group_2_srs(qap.degree(), tau);
+        let configured_group_2_srs = ConfiguredSrs::new(&group_2_srs, config);
 
         debug!("Generated Group 2 SRS");
 
-        let zero_polynomial_srs =
-            Self::zero_polynomial_srs(qap.degree(), qap.degree() - 1, delta, &group_1_srs)
-                .context("Calculating zero polynomial SRS")?;
+        let t = Self::t(&qap.domain).context("Calculating zero polynomial")?;
+        let zero_polynomial_srs = Self::generate_zero_polynomial_srs(
+            &t,
+            zero_polynomial_srs_length,
+            delta,
+            &configured_group_1_srs,
+        )
+        .context("Calculating zero polynomial SRS")?;
 
         debug!("Generated zero polynomial srs");
 
-        let psi_polynomials = Self::psi_polynomials(&qap, &group_1_srs, alpha, beta, gamma, delta)
-            .context("Generating psi polynomials")?;
+        let zero_polynomial_srs_2 = if config.zero_polynomial_srs_2 {
+            // x^i * t(x) needs as many powers of tau as the group 1 SRS has, rather than the group 2 SRS's
+            let long_group_2_srs = Self::generate_group_2_srs(group_1_srs_length, tau);
+            Self::generate_zero_polynomial_srs(
+                &t,
+                zero_polynomial_srs_length,
+                delta,
+                &ConfiguredSrs::new(&long_group_2_srs, config),
+            )
+            .context("Calculating group 2 zero polynomial SRS")?
+        } else {
+            Vec::new()
+        };
+
+        let psi_polynomials = Self::generate_psi_polynomials(
+            &qap,
+            &configured_group_1_srs,
+            alpha,
+            beta,
+            gamma,
+            delta,
+        )
+        .context("Generating psi polynomials")?;
 
         debug!("Generated psi polynomials");
 
+        let u_tau = Self::evaluate_polynomials(&qap.u, &configured_group_1_srs)
+            .context("Evaluating u(x) over group 1 SRS")?;
+        let v_tau_1 = Self::evaluate_polynomials(&qap.v, &configured_group_1_srs)
+            .context("Evaluating v(x) over group 1 SRS")?;
+        let v_tau_2 = Self::evaluate_polynomials(&qap.v, &configured_group_2_srs)
+            .context("Evaluating v(x) over group 2 SRS")?;
+
+        debug!("Evaluated QAP polynomials");
+
         Ok(TrustedSetupOutput {
             qap,
             alpha: C::G1::generator() * alpha,
@@ -207,156 +1065,982 @@ impl<C: Pairing> TrustedSetupOutput<C> {
             group_1_srs,
             group_2_srs,
             zero_polynomial_srs,
+            zero_polynomial_srs_2,
             psi_polynomials,
+            u_tau,
+            v_tau_1,
+            v_tau_2,
         })
     }
 
-    fn calculate_zero_polynomial(
+    /// Check SRSs of these lengths cover every polynomial that setup and proving evaluate over them, so a QAP whose
+    /// polynomials have a higher degree than its domain allows fails with `Groth16Error::SrsTooSmall` here rather than
+    /// partway through.
+    fn check_srs_lengths(
+        qap: &QAP<C::ScalarField>,
+        group_1_srs_length: usize,
+        group_2_srs_length: usize,
+        zero_polynomial_srs_length: usize,
+    ) -> Result<(), Report> {
+        let vanishing_degree = qap.domain.len();
+        // u(x), v(x), and w(x) are evaluated over both SRSs
+        let polynomial_length = qap.max_polynomial_degree() + 1;
+        // x^i * t(x) is evaluated over the group 1 SRS for each element of the zero polynomial SRS
+        let shifted_vanishing_length = match zero_polynomial_srs_length {
+            0 => 0,
+            length => length + vanishing_degree,
+        };
+        // h(x) = (A(x) * B(x) - C(x)) / t(x) is evaluated over the zero polynomial SRS
+        let quotient_length = (2 * polynomial_length).saturating_sub(vanishing_degree + 1);
+
+        for (needed, have, srs) in [
+            (
+                polynomial_length.max(shifted_vanishing_length),
+                group_1_srs_length,
+                "Group 1 SRS",
+            ),
+            (polynomial_length, group_2_srs_length, "Group 2 SRS"),
+            (
+                quotient_length,
+                zero_polynomial_srs_length,
+                "Zero polynomial SRS",
+            ),
+        ] {
+            if needed > have {
+                return Err(report!(Groth16Error::SrsTooSmall { needed, have })
+                    .attach(format!(
+                        "{srs} too small for a QAP of degree {} over a domain of {vanishing_degree} points",
+                        qap.degree()
+                    ))
+                    .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// The quotient `h(x) = (A(x) * B(x) - C(x)) / t(x)` proving commits to, as `QAP::quotient` for the setup's QAP.
+    /// Useful for diagnosing a witness that won't prove: if it doesn't satisfy the QAP this fails with
+    /// `Groth16Error::NonZeroRemainder`, with the numerator and `t(x)` attached.
+    pub fn quotient_polynomial(
         &self,
-        witness: &Vec<C::ScalarField>,
+        witness: &[C::ScalarField],
     ) -> Result<Polynomial<C::ScalarField>, Report> {
-        let au_sum: Polynomial<C::ScalarField> =
-            zip(&self.qap.u, witness).map(|(u_i, a_i)| u_i * *a_i).sum();
-        let av_sum: Polynomial<C::ScalarField> =
-            zip(&self.qap.v, witness).map(|(v_i, a_i)| v_i * *a_i).sum();
-        let aw_sum: Polynomial<C::ScalarField> =
-            zip(&self.qap.w, witness).map(|(w_i, a_i)| w_i * *a_i).sum();
-        Ok(
-            ((&(au_sum * av_sum) - &aw_sum)
-                / Self::t(self.qap.degree()).context("Generating t")?)
-            .context("Dividing u(x)*v(x) - w(x) by t to find h")?,
-        )
+        self.qap.quotient(witness)
     }
 
-    fn evaluate_u(&self, witness: &Vec<C::ScalarField>) -> Result<C::G1, Report> {
-        let evaluated_u = self
-            .qap
-            .u
+    /// Evaluate each polynomial at tau over the given SRS.
+    fn evaluate_polynomials<G>(
+        polynomials: &[Polynomial<C::ScalarField>],
+        srs: &ConfiguredSrs<G>,
+    ) -> Result<Vec<G>, Report>
+    where
+        G: CurveGroup<ScalarField = C::ScalarField>,
+    {
+        polynomials
             .iter()
-            .map(|x| x.evaluate_over_srs(&self.group_1_srs))
+            .map(|x| srs.evaluate(x))
             .collect::<Result<Vec<_>, Report>>()
-            .context("Evaluating u(x) over SRS")?;
+    }
 
-        Ok(zip(evaluated_u, witness)
-            .map(|(p, a_i)| p * a_i)
-            .collect::<Vec<_>>()
-            .into_iter()
-            .reduce(std::ops::Add::add)
-            .ok_or(report!("Empty witness"))
-            .context("Multiplying u(x) by witness")?)
+    /// Weight each evaluated polynomial by its witness element and sum them, as a single multi-scalar multiplication.
+    /// Extra elements of either slice are ignored, and if either is empty the sum is the identity.
+    /// Callers validate the witness first, so an empty sum here is legitimate, e.g. the private psi term of a circuit
+    /// whose wires are all public.
+    fn combine<G>(evaluated: &[G], witness: &[C::ScalarField]) -> G
+    where
+        G: CurveGroup<ScalarField = C::ScalarField>,
+    {
+        let len = evaluated.len().min(witness.len());
+        let bases = G::normalize_batch(&evaluated[..len]);
+        G::msm_unchecked(&bases, &witness[..len])
     }
 
-    fn evaluate_v(&self, witness: &Vec<C::ScalarField>) -> Result<C::G2, Report> {
-        let evaluated_v = self
-            .qap
-            .v
-            .iter()
-            .map(|x| x.evaluate_over_srs(&self.group_2_srs))
-            .collect::<Result<Vec<_>, Report>>()
-            .context("Evaluating v(x) over group 2 SRS")?;
+    fn evaluate_u(&self, witness: &[C::ScalarField]) -> C::G1 {
+        Self::combine(&self.u_tau, witness)
+    }
 
-        Ok(zip(evaluated_v, witness)
-            .map(|(p, a_i)| p * a_i)
-            .collect::<Vec<_>>()
-            .into_iter()
-            .reduce(std::ops::Add::add)
-            .ok_or(report!("Empty witness"))
-            .context("Multiplying v(x) by witness")?)
+    fn evaluate_v(&self, witness: &[C::ScalarField]) -> C::G2 {
+        Self::combine(&self.v_tau_2, witness)
     }
 
-    fn evaluate_v_1(&self, witness: &Vec<C::ScalarField>) -> Result<C::G1, Report> {
-        let evaluated_v = self
-            .qap
-            .v
-            .iter()
-            .map(|x| x.evaluate_over_srs(&self.group_1_srs))
-            .collect::<Result<Vec<_>, Report>>()
-            .context("Evaluating v(x) over group 1 SRS")?;
+    fn evaluate_v_1(&self, witness: &[C::ScalarField]) -> C::G1 {
+        Self::combine(&self.v_tau_1, witness)
+    }
 
-        Ok(zip(evaluated_v, witness)
-            .map(|(p, a_i)| p * a_i)
-            .collect::<Vec<_>>()
-            .into_iter()
-            .reduce(std::ops::Add::add)
-            .ok_or(report!("Empty witness"))
-            .context("Multiplying v(x) by witness")?)
+    /// Only used in tests, full algorithm uses psi polynomials instead of evaluating w over an SRS, so w(x) isn't
+    /// cached alongside u(x) and v(x).
+    #[cfg(test)]
+    fn evaluate_w(&self, witness: &[C::ScalarField]) -> Result<C::G1, Report> {
+        let w_tau = Self::evaluate_polynomials(
+            &self.qap.w,
+            &ConfiguredSrs::new(&self.group_1_srs, &SetupConfig::default()),
+        )
+        .context("Evaluating w(x) over group 1 SRS")?;
+        Ok(Self::combine(&w_tau, witness))
     }
 
-    /// Only used in tests, full algorithm uses psi polynomials instead of evaluating w over an SRS.
-    fn evaluate_w(&self, witness: &Vec<C::ScalarField>) -> Result<C::G1, Report> {
-        let evaluated_w = self
-            .qap
-            .w
-            .iter()
-            .map(|x| x.evaluate_over_srs(&self.group_1_srs))
-            .collect::<Result<Vec<_>, Report>>()?;
+    /// Prove with the witness given as its public and private parts, rather than one flat witness.
+    /// Fails with `Groth16Error::PublicWitnessMismatch` unless `public` matches the public witness the QAP was
+    /// created with, so inconsistent public inputs can't silently produce a proof that won't verify.
+    pub fn prove_split(
+        &self,
+        public: &[C::ScalarField],
+        private: &[C::ScalarField],
+    ) -> Result<Proof<C>, Report> {
+        if public != self.qap.public_witness.as_slice() {
+            return Err(report!(Groth16Error::PublicWitnessMismatch)
+                .attach(format!("Expected: {:?}", self.qap.public_witness))
+                .attach(format!("Got: {:?}", public))
+                .into());
+        }
 
-        Ok(zip(evaluated_w, witness)
-            .map(|(p, a_i)| p * a_i)
-            .collect::<Vec<_>>()
-            .into_iter()
-            .reduce(std::ops::Add::add)
-            .ok_or(report!("Empty witness"))?)
+        Ok(self
+            .prove(&[public, private].concat())
+            .context("Proving with split witness")?)
     }
 
-    pub fn prove(&self, witness: &Vec<C::ScalarField>) -> Result<Proof<C>, Report> {
-        if witness.len() != self.qap.u.len()
-            || witness.len() != self.qap.v.len()
-            || witness.len() != self.qap.w.len()
-        {
-            bail!("Witness incorrect length!");
-        }
+    pub fn prove(&self, witness: &[C::ScalarField]) -> Result<Proof<C>, Report> {
+        self.prove_with_rng(witness, &mut rand::rngs::StdRng::from_os_rng())
+    }
 
-        let mut rng = rand::rngs::StdRng::from_os_rng();
+    /// Prove as in `prove`, drawing the blinding scalars `r` and `s` from `rng`.
+    /// The same setup, witness, and RNG state always give the same proof, so this is mostly useful for testing.
+    ///
+    /// The witness is checked before anything is evaluated, failing with `Groth16Error::NoWires` if the setup's QAP
+    /// has no columns, `Groth16Error::EmptyWitness` if the witness is empty, or `Groth16Error::WitnessLength` if
+    /// it's the wrong length.
+    pub fn prove_with_rng<R: rand::Rng>(
+        &self,
+        witness: &[C::ScalarField],
+        rng: &mut R,
+    ) -> Result<Proof<C>, Report> {
+        self.check_witness(witness)?;
+        let h = self
+            .quotient_polynomial(witness)
+            .context("Calculating quotient polynomial")?;
+        self.prove_checked(witness, &h, rng)
+    }
 
-        let r: C::ScalarField = rand_scalar(&mut rng);
-        let s: C::ScalarField = rand_scalar(&mut rng);
+    /// Prove as in `prove_with_rng`, with the quotient `h(x) = (A(x) * B(x) - C(x)) / t(x)` supplied rather than
+    /// computed, e.g. by a faster external routine. `h` isn't checked against the witness, so a wrong `h` gives a
+    /// proof that doesn't verify. Fails with `Groth16Error::SrsTooSmall` if `h` has more coefficients than the zero
+    /// polynomial SRS, and otherwise checks the witness as `prove_with_rng` does.
+    pub fn prove_with_quotient<R: rand::Rng>(
+        &self,
+        witness: &[C::ScalarField],
+        h: &Polynomial<C::ScalarField>,
+        rng: &mut R,
+    ) -> Result<Proof<C>, Report> {
+        self.check_witness(witness)?;
+        self.prove_checked(witness, h, rng)
+    }
 
-        let a = self.alpha
-            + self.evaluate_u(witness).context("Evaluating a*u(x)")?
-            + (self.delta_1 * r);
+    /// Prove as in `prove_with_quotient` for a witness that's already been through `check_witness`.
+    fn prove_checked<R: rand::Rng>(
+        &self,
+        witness: &[C::ScalarField],
+        h: &Polynomial<C::ScalarField>,
+        rng: &mut R,
+    ) -> Result<Proof<C>, Report> {
+        let h = h.trimmed();
+        if h.len() > self.zero_polynomial_srs.len() {
+            return Err(report!(Groth16Error::SrsTooSmall {
+                needed: h.len(),
+                have: self.zero_polynomial_srs.len(),
+            })
+            .attach("h(x) has too high a degree for the zero polynomial SRS")
+            .into());
+        }
 
-        let b_2 = self.beta_2
-            + self
-                .evaluate_v(witness)
-                .context("Evaluating a*v(x) in group 2")?
-            + (self.delta_2 * s);
+        let r: C::ScalarField = rand_scalar(rng);
+        let s: C::ScalarField = rand_scalar(rng);
 
-        let b_1 = self.beta_1
-            + self
-                .evaluate_v_1(witness)
-                .context("Evaluating a*v(x) in group 1")?
-            + (self.delta_1 * s);
+        let a = self.alpha + self.evaluate_u(witness) + (self.delta_1 * r);
+        let b_2 = self.beta_2 + self.evaluate_v(witness) + (self.delta_2 * s);
+        let b_1 = self.beta_1 + self.evaluate_v_1(witness) + (self.delta_1 * s);
 
-        let ht = self
-            .calculate_zero_polynomial(witness)
-            .context("Calculating zero polynomial")?;
-        let ht_tau = ht
+        let ht_tau = h
             .evaluate_over_srs(&self.zero_polynomial_srs)
             .context("Evaluating zero polynomial at tau")?;
 
-        let c = zip(&self.psi_polynomials, witness)
-            .skip(self.qap.public_witness.len())
-            .map(|(psi, a_i)| *psi * a_i)
-            .reduce(std::ops::Add::add)
-            .ok_or(report!("Empty witness"))
-            .context("Multiplying witness by psi polynomials")?
-            + ht_tau
+        let public_len = self.qap.public_witness.len();
+        let c = Self::combine(
+            self.psi_polynomials.get(public_len..).unwrap_or_default(),
+            &witness[public_len..],
+        ) + ht_tau
             + (a * s)
             + (b_1 * r)
             - (self.delta_1 * (r * s));
         Ok(Proof { a, b: b_2, c })
     }
+
+    /// Check a witness can be proven for, as described on `prove_with_rng`.
+    fn check_witness(&self, witness: &[C::ScalarField]) -> Result<(), Report> {
+        if self.qap.u.is_empty() {
+            return Err(report!(Groth16Error::NoWires)
+                .attach("Trusted setup's QAP has no columns")
+                .into());
+        }
+        if witness.is_empty() {
+            bail!(Groth16Error::EmptyWitness);
+        }
+        if witness.len() != self.qap.u.len()
+            || witness.len() != self.qap.v.len()
+            || witness.len() != self.qap.w.len()
+        {
+            bail!(Groth16Error::WitnessLength {
+                expected: self.qap.u.len(),
+                got: witness.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Serialize with each distinct group element written once, and every field stored as indices into the
+    /// tables of distinct G1 and G2 elements. Smaller than the plain serialization for circuits with repeated
+    /// elements, e.g. many public inputs with identical psi values. Read back with `deserialize_deduplicated`.
+    pub fn serialize_deduplicated(&self) -> Vec<u8> {
+        let mut g1_table = PointTable::default();
+        let g1_indices: Vec<Vec<u32>> = [
+            std::slice::from_ref(&self.alpha),
+            std::slice::from_ref(&self.beta_1),
+            std::slice::from_ref(&self.delta_1),
+            &self.group_1_srs,
+            &self.zero_polynomial_srs,
+            &self.psi_polynomials,
+            &self.u_tau,
+            &self.v_tau_1,
+        ]
+        .iter()
+        .map(|points| g1_table.insert_all(points))
+        .collect();
+
+        let mut g2_table = PointTable::default();
+        let g2_indices: Vec<Vec<u32>> = [
+            std::slice::from_ref(&self.beta_2),
+            std::slice::from_ref(&self.gamma),
+            std::slice::from_ref(&self.delta_2),
+            &self.group_2_srs,
+            &self.v_tau_2,
+            &self.zero_polynomial_srs_2,
+        ]
+        .iter()
+        .map(|points| g2_table.insert_all(points))
+        .collect();
+
+        let mut bytes = vec![];
+        (
+            self.qap.clone(),
+            g1_table.points,
+            g2_table.points,
+            g1_indices,
+            g2_indices,
+        )
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+        bytes
+    }
+
+    /// Write the setup to `writer` in the form produced by `serialize_deduplicated`.
+    /// The setup holds everything `prove` needs precomputed, i.e. the QAP (and so its vanishing polynomial) and
+    /// the QAP polynomials evaluated over the SRS, so a prover can load it with `from_reader` rather than recompute it.
+    pub fn to_writer<W: std::io::Write>(&self, mut writer: W) -> Result<(), Report> {
+        Ok(writer
+            .write_all(&self.serialize_deduplicated())
+            .context("Writing trusted setup")?)
+    }
+
+    /// Read a setup written by `to_writer`, consuming the rest of `reader`.
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Result<Self, Report> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .context("Reading trusted setup")?;
+        Self::deserialize_deduplicated(&bytes)
+    }
+
+    /// Inverse of `serialize_deduplicated`.
+    pub fn deserialize_deduplicated(bytes: &[u8]) -> Result<Self, Report> {
+        let (qap, g1_table, g2_table, g1_indices, g2_indices): DeduplicatedSetup<C> =
+            CanonicalDeserialize::deserialize_compressed(bytes).map_err(|e| {
+                report!(Groth16Error::InvalidEncoding)
+                    .attach(format!("{e}"))
+                    .context("Deserializing deduplicated trusted setup")
+            })?;
+
+        let [
+            alpha,
+            beta_1,
+            delta_1,
+            group_1_srs,
+            zero_polynomial_srs,
+            psi_polynomials,
+            u_tau,
+            v_tau_1,
+        ] = <[Vec<u32>; 8]>::try_from(g1_indices)
+            .map_err(|_| report!(Groth16Error::InvalidEncoding))
+            .attach("Expected 8 G1 fields")?
+            .map(|indices| lookup(&g1_table, &indices));
+        let [
+            beta_2,
+            gamma,
+            delta_2,
+            group_2_srs,
+            v_tau_2,
+            zero_polynomial_srs_2,
+        ] = <[Vec<u32>; 6]>::try_from(g2_indices)
+            .map_err(|_| report!(Groth16Error::InvalidEncoding))
+            .attach("Expected 6 G2 fields")?
+            .map(|indices| lookup(&g2_table, &indices));
+
+        Ok(TrustedSetupOutput {
+            qap,
+            alpha: single(alpha).context("Reading alpha")?,
+            beta_1: single(beta_1).context("Reading beta_1")?,
+            beta_2: single(beta_2).context("Reading beta_2")?,
+            gamma: single(gamma).context("Reading gamma")?,
+            delta_1: single(delta_1).context("Reading delta_1")?,
+            delta_2: single(delta_2).context("Reading delta_2")?,
+            group_1_srs: group_1_srs.context("Reading group_1_srs")?,
+            group_2_srs: group_2_srs.context("Reading group_2_srs")?,
+            zero_polynomial_srs: zero_polynomial_srs.context("Reading zero_polynomial_srs")?,
+            zero_polynomial_srs_2: zero_polynomial_srs_2
+                .context("Reading zero_polynomial_srs_2")?,
+            psi_polynomials: psi_polynomials.context("Reading psi_polynomials")?,
+            u_tau: u_tau.context("Reading u_tau")?,
+            v_tau_1: v_tau_1.context("Reading v_tau_1")?,
+            v_tau_2: v_tau_2.context("Reading v_tau_2")?,
+        })
+    }
+}
+
+/// Run a new trusted setup for a QAP, as `TrustedSetupOutput::new`.
+/// The setup is the proving key, and `TrustedSetupOutput::verifying_key_affine` gives the verifying key.
+pub fn setup<C: Pairing>(qap: QAP<C::ScalarField>) -> Result<TrustedSetupOutput<C>, Report> {
+    TrustedSetupOutput::new(qap)
+}
+
+/// Prove for a witness over a trusted setup, as `TrustedSetupOutput::prove`.
+pub fn prove<C: Pairing>(
+    proving_key: &TrustedSetupOutput<C>,
+    witness: &[C::ScalarField],
+) -> Result<Proof<C>, Report> {
+    proving_key.prove(witness)
+}
+
+/// Verify a proof against a public witness with just the verifying key, as `Proof::verify_with_key`.
+pub fn verify<C: Pairing>(
+    verifying_key: &VerifyingKeyAffine<C>,
+    proof: &Proof<C>,
+    public_witness: &[C::ScalarField],
+) -> Result<bool, Report> {
+    proof.verify_with_key(verifying_key, public_witness)
+}
+
+/// The QAP, the tables of distinct G1 and G2 points, then the indices into them for each G1 and G2 field.
+type DeduplicatedSetup<C> = (
+    QAP<<C as Pairing>::ScalarField>,
+    Vec<<C as Pairing>::G1>,
+    Vec<<C as Pairing>::G2>,
+    Vec<Vec<u32>>,
+    Vec<Vec<u32>>,
+);
+
+/// The distinct points seen so far, used by `TrustedSetupOutput::serialize_deduplicated`.
+struct PointTable<G> {
+    points: Vec<G>,
+    indices: HashMap<G, u32>,
+}
+
+impl<G> Default for PointTable<G> {
+    fn default() -> Self {
+        PointTable {
+            points: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<G: CurveGroup> PointTable<G> {
+    /// Add the points to the table, returning the index of each.
+    fn insert_all(&mut self, points: &[G]) -> Vec<u32> {
+        points
+            .iter()
+            .map(|point| {
+                *self.indices.entry(*point).or_insert_with(|| {
+                    self.points.push(*point);
+                    (self.points.len() - 1) as u32
+                })
+            })
+            .collect()
+    }
+}
+
+/// Unwrap the single point a field like `alpha` is stored as.
+fn single<G: CurveGroup>(points: Result<Vec<G>, Report>) -> Result<G, Report> {
+    match points?.as_slice() {
+        [point] => Ok(*point),
+        points => Err(report!(Groth16Error::InvalidEncoding)
+            .attach(format!("Expected a single point, got {}", points.len()))
+            .into()),
+    }
+}
+
+/// Look up each index in a table of distinct points.
+fn lookup<G: CurveGroup>(table: &[G], indices: &[u32]) -> Result<Vec<G>, Report> {
+    indices
+        .iter()
+        .map(|i| {
+            table.get(*i as usize).copied().ok_or_else(|| {
+                report!(Groth16Error::InvalidEncoding)
+                    .attach(format!("Index {i} out of range of {} points", table.len()))
+                    .into()
+            })
+        })
+        .collect()
+}
+
+/// A pairing whose curve is supported by the EVM's BN254 precompiles, so proofs can be verified on-chain.
+/// Only implemented for BN254, MNT6-753 (and BLS12-381) have no precompiles so can't be verified on the EVM.
+#[cfg(feature = "solidity")]
+pub trait SolidityPairing: Pairing {
+    /// Affine `[x, y]` coordinates as decimal strings, with the identity as `[0, 0]`.
+    fn g1_decimal(point: &Self::G1) -> [String; 2];
+    /// Affine coordinates as decimal strings in the EVM's order, `[[x.c1, x.c0], [y.c1, y.c0]]`.
+    fn g2_decimal(point: &Self::G2) -> [[String; 2]; 2];
+    /// Inverse of `g1_decimal`, checking the point is on the curve and in the right subgroup.
+    fn g1_from_decimal(coordinates: &[String; 2]) -> Result<Self::G1, Report>;
+    /// Inverse of `g2_decimal`, checking the point is on the curve and in the right subgroup.
+    fn g2_from_decimal(coordinates: &[[String; 2]; 2]) -> Result<Self::G2, Report>;
+}
+
+/// Parse a decimal string into a field element.
+#[cfg(feature = "solidity")]
+fn field_from_decimal<F: std::str::FromStr>(decimal: &str) -> Result<F, Report> {
+    F::from_str(decimal).map_err(|_| {
+        report!(Groth16Error::InvalidEncoding)
+            .attach(format!("{decimal} is not a field element"))
+            .into()
+    })
+}
+
+#[cfg(feature = "solidity")]
+impl SolidityPairing for ark_bn254::Bn254 {
+    fn g1_decimal(point: &Self::G1) -> [String; 2] {
+        let point = point.into_affine();
+        if point.infinity {
+            return ["0".to_string(), "0".to_string()];
+        }
+        [point.x.to_string(), point.y.to_string()]
+    }
+
+    fn g2_decimal(point: &Self::G2) -> [[String; 2]; 2] {
+        let point = point.into_affine();
+        if point.infinity {
+            return [
+                ["0".to_string(), "0".to_string()],
+                ["0".to_string(), "0".to_string()],
+            ];
+        }
+        [
+            [point.x.c1.to_string(), point.x.c0.to_string()],
+            [point.y.c1.to_string(), point.y.c0.to_string()],
+        ]
+    }
+
+    fn g1_from_decimal(coordinates: &[String; 2]) -> Result<Self::G1, Report> {
+        if coordinates.iter().all(|c| c == "0") {
+            return Ok(Self::G1::default());
+        }
+        let point = ark_bn254::G1Affine::new_unchecked(
+            field_from_decimal(&coordinates[0])?,
+            field_from_decimal(&coordinates[1])?,
+        );
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(report!(Groth16Error::InvalidEncoding)
+                .attach(format!("{coordinates:?} is not a G1 point"))
+                .into());
+        }
+        Ok(point.into())
+    }
+
+    fn g2_from_decimal(coordinates: &[[String; 2]; 2]) -> Result<Self::G2, Report> {
+        if coordinates.iter().flatten().all(|c| c == "0") {
+            return Ok(Self::G2::default());
+        }
+        let point = ark_bn254::G2Affine::new_unchecked(
+            ark_bn254::Fq2::new(
+                field_from_decimal(&coordinates[0][1])?,
+                field_from_decimal(&coordinates[0][0])?,
+            ),
+            ark_bn254::Fq2::new(
+                field_from_decimal(&coordinates[1][1])?,
+                field_from_decimal(&coordinates[1][0])?,
+            ),
+        );
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(report!(Groth16Error::InvalidEncoding)
+                .attach(format!("{coordinates:?} is not a G2 point"))
+                .into());
+        }
+        Ok(point.into())
+    }
+}
+
+#[cfg(feature = "solidity")]
+impl<C: SolidityPairing> Proof<C> {
+    /// Serialize to the JSON shape snarkjs uses for Groth16 proofs, so it can be checked with `snarkjs groth16 verify`.
+    /// snarkjs stores points in Jacobian form with `z = 1`, and unlike the EVM keeps G2 coordinates as `[c0, c1]`.
+    pub fn to_snarkjs_json(&self) -> serde_json::Value {
+        let g1 = |point: &C::G1| {
+            let [x, y] = C::g1_decimal(point);
+            if x == "0" && y == "0" {
+                serde_json::json!(["0", "1", "0"])
+            } else {
+                serde_json::json!([x, y, "1"])
+            }
+        };
+        let [[x_c1, x_c0], [y_c1, y_c0]] = C::g2_decimal(&self.b);
+        let b = if [&x_c1, &x_c0, &y_c1, &y_c0].iter().all(|c| *c == "0") {
+            serde_json::json!([["0", "0"], ["1", "0"], ["0", "0"]])
+        } else {
+            serde_json::json!([[x_c0, x_c1], [y_c0, y_c1], ["1", "0"]])
+        };
+
+        serde_json::json!({
+            "pi_a": g1(&self.a),
+            "pi_b": b,
+            "pi_c": g1(&self.c),
+            "protocol": "groth16",
+            "curve": "bn128",
+        })
+    }
+
+    /// Parse a proof from the JSON shape snarkjs uses, the inverse of `to_snarkjs_json`.
+    /// Points must be in affine form (`z = 1`) or the identity (`z = 0`), as snarkjs always writes them.
+    pub fn from_snarkjs_json(json: &serde_json::Value) -> Result<Self, Report> {
+        let decimal = |value: &serde_json::Value| -> Result<String, Report> {
+            value.as_str().map(str::to_string).ok_or_else(|| {
+                report!(Groth16Error::InvalidEncoding)
+                    .attach(format!("{value} is not a decimal string"))
+                    .into()
+            })
+        };
+        let g1 = |name: &str| -> Result<C::G1, Report> {
+            let point = &json[name];
+            match decimal(&point[2])?.as_str() {
+                "0" => Ok(C::G1::default()),
+                "1" => C::g1_from_decimal(&[decimal(&point[0])?, decimal(&point[1])?]),
+                _ => Err(report!(Groth16Error::InvalidEncoding)
+                    .attach(format!("{name} is not in affine form"))
+                    .into()),
+            }
+        };
+
+        let point = &json["pi_b"];
+        let b = match (
+            decimal(&point[2][0])?.as_str(),
+            decimal(&point[2][1])?.as_str(),
+        ) {
+            ("0", "0") => C::G2::default(),
+            ("1", "0") => C::g2_from_decimal(&[
+                [decimal(&point[0][1])?, decimal(&point[0][0])?],
+                [decimal(&point[1][1])?, decimal(&point[1][0])?],
+            ])?,
+            _ => {
+                return Err(report!(Groth16Error::InvalidEncoding)
+                    .attach("pi_b is not in affine form")
+                    .into());
+            }
+        };
+
+        Ok(Proof {
+            a: g1("pi_a").context("Parsing pi_a")?,
+            b,
+            c: g1("pi_c").context("Parsing pi_c")?,
+        })
+    }
+}
+
+/// Export a Solidity contract that verifies proofs on the EVM against the given verifying key, as from
+/// `TrustedSetupOutput::verifying_key_affine`.
+/// The contract's `verifyProof` matches the snarkjs ABI, taking `B` with its G2 coordinates in EVM order
+/// (`[[x.c1, x.c0], [y.c1, y.c0]]`) followed by the public witness, with one public input per psi polynomial in
+/// the key.
+/// The public witness is combined with the hardcoded public psi polynomials exactly as `Proof::verify` does.
+#[cfg(feature = "solidity")]
+pub fn export_solidity_verifier<C: SolidityPairing>(
+    verifying_key: &VerifyingKeyAffine<C>,
+) -> String {
+    use std::fmt::Write;
+
+    let num_public = verifying_key.psi_polynomials.len();
+    let alpha = C::g1_decimal(&verifying_key.alpha.into());
+    let g2_constants = [
+        ("beta", C::g2_decimal(&verifying_key.beta_2.into())),
+        ("gamma", C::g2_decimal(&verifying_key.gamma.into())),
+        ("delta", C::g2_decimal(&verifying_key.delta_2.into())),
+    ];
+
+    let mut out = String::new();
+    out.push_str("// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;\n\n");
+    out.push_str(
+        "/// Groth16 verifier, checks e(-A, B) * e(alpha, beta) * e(x, gamma) * e(C, delta) == 1\n",
+    );
+    out.push_str("contract Groth16Verifier {\n");
+    let _ = writeln!(
+        out,
+        "    uint256 constant SCALAR_FIELD = {};",
+        <C::ScalarField as PrimeField>::MODULUS
+    );
+    let _ = writeln!(
+        out,
+        "    uint256 constant BASE_FIELD = {};\n",
+        <C::BaseField as PrimeField>::MODULUS
+    );
+    let _ = writeln!(out, "    uint256 constant alphax = {};", alpha[0]);
+    let _ = writeln!(out, "    uint256 constant alphay = {};", alpha[1]);
+    for (name, point) in &g2_constants {
+        let _ = writeln!(out, "    uint256 constant {name}x1 = {};", point[0][0]);
+        let _ = writeln!(out, "    uint256 constant {name}x2 = {};", point[0][1]);
+        let _ = writeln!(out, "    uint256 constant {name}y1 = {};", point[1][0]);
+        let _ = writeln!(out, "    uint256 constant {name}y2 = {};", point[1][1]);
+    }
+    for (i, psi) in verifying_key.psi_polynomials.iter().enumerate() {
+        let psi = C::g1_decimal(&(*psi).into());
+        let _ = writeln!(out, "    uint256 constant psi{i}x = {};", psi[0]);
+        let _ = writeln!(out, "    uint256 constant psi{i}y = {};", psi[1]);
+    }
+
+    out.push_str(
+        r#"
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory) {
+        (bool success, bytes memory out) = address(6).staticcall(abi.encode(p1[0], p1[1], p2[0], p2[1]));
+        require(success, "ecAdd failed");
+        return abi.decode(out, (uint256[2]));
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory) {
+        (bool success, bytes memory out) = address(7).staticcall(abi.encode(p[0], p[1], s));
+        require(success, "ecMul failed");
+        return abi.decode(out, (uint256[2]));
+    }
+
+"#,
+    );
+
+    out.push_str("    function verifyProof(\n        uint[2] calldata _pA,\n        uint[2][2] calldata _pB,\n        uint[2] calldata _pC");
+    if num_public > 0 {
+        let _ = write!(out, ",\n        uint[{num_public}] calldata _pubSignals");
+    }
+    out.push_str("\n    ) public view returns (bool) {\n");
+    out.push_str("        uint256[2] memory x = [uint256(0), uint256(0)];\n");
+    for i in 0..num_public {
+        let _ = writeln!(
+            out,
+            "        require(_pubSignals[{i}] < SCALAR_FIELD, \"Public signal out of range\");"
+        );
+        let _ = writeln!(
+            out,
+            "        x = ecAdd(x, ecMul([psi{i}x, psi{i}y], _pubSignals[{i}]));"
+        );
+    }
+    out.push_str(
+        r#"
+        uint256[24] memory input = [
+            _pA[0], (BASE_FIELD - _pA[1] % BASE_FIELD) % BASE_FIELD,
+            _pB[0][0], _pB[0][1], _pB[1][0], _pB[1][1],
+            alphax, alphay,
+            betax1, betax2, betay1, betay2,
+            x[0], x[1],
+            gammax1, gammax2, gammay1, gammay2,
+            _pC[0], _pC[1],
+            deltax1, deltax2, deltay1, deltay2
+        ];
+        (bool success, bytes memory out) = address(8).staticcall(abi.encodePacked(input));
+        return success && abi.decode(out, (uint256)) == 1;
+    }
+}
+"#,
+    );
+    out
+}
+
+/// A domain-separated Fiat-Shamir transcript over group elements and scalars.
+/// Absorbs the compressed canonical serialization of each appended element into a SHA-512 hash,
+/// and squeezes challenge scalars from it. Useful for binding proofs to a context or batching.
+#[cfg(feature = "transcript")]
+#[derive(Clone)]
+pub struct Transcript<C: Pairing> {
+    hasher: Sha512,
+    _curve: PhantomData<C>,
+}
+
+#[cfg(feature = "transcript")]
+impl<C: Pairing> Transcript<C> {
+    /// Start a new transcript, separated from transcripts for other protocols by `domain`.
+    pub fn new(domain: &[u8]) -> Self {
+        let mut hasher = Sha512::new();
+        hasher.update((domain.len() as u64).to_le_bytes());
+        hasher.update(domain);
+        Transcript {
+            hasher,
+            _curve: PhantomData,
+        }
+    }
+
+    fn append<T: CanonicalSerialize>(&mut self, label: &[u8], element: &T) -> Result<(), Report> {
+        let mut bytes = vec![];
+        element
+            .serialize_compressed(&mut bytes)
+            .context("Serializing transcript element")?;
+        self.hasher.update(label);
+        self.hasher.update((bytes.len() as u64).to_le_bytes());
+        self.hasher.update(&bytes);
+        Ok(())
+    }
+
+    /// Absorb a G1 element.
+    pub fn append_g1(&mut self, element: &C::G1) -> Result<(), Report> {
+        self.append(b"g1", element)
+            .context("Appending G1 element to transcript")?;
+        Ok(())
+    }
+
+    /// Absorb a G2 element.
+    pub fn append_g2(&mut self, element: &C::G2) -> Result<(), Report> {
+        self.append(b"g2", element)
+            .context("Appending G2 element to transcript")?;
+        Ok(())
+    }
+
+    /// Absorb a scalar.
+    pub fn append_scalar(&mut self, scalar: &C::ScalarField) -> Result<(), Report> {
+        self.append(b"scalar", scalar)
+            .context("Appending scalar to transcript")?;
+        Ok(())
+    }
+
+    /// Squeeze a challenge scalar from everything absorbed so far.
+    /// The challenge is itself absorbed, so consecutive calls return different challenges.
+    ///
+    /// Digests of the transcript with successive counters are concatenated to 128 bits more than the modulus, then
+    /// reduced, so the challenge is statistically close to uniform even for fields wider than one digest.
+    pub fn challenge_scalar(&mut self) -> Result<C::ScalarField, Report> {
+        let length = (C::ScalarField::MODULUS_BIT_SIZE as usize + 128).div_ceil(8);
+        let mut bytes = Vec::with_capacity(length + 64);
+        let mut counter: u64 = 0;
+        while bytes.len() < length {
+            let mut hasher = self.hasher.clone();
+            hasher.update(b"challenge");
+            hasher.update(counter.to_le_bytes());
+            bytes.extend(hasher.finalize());
+            counter += 1;
+        }
+        bytes.truncate(length);
+        let challenge = C::ScalarField::from_le_bytes_mod_order(&bytes);
+
+        self.append_scalar(&challenge)
+            .context("Absorbing challenge")?;
+        Ok(challenge)
+    }
+}
+
+/// One party's contribution to a trusted setup ceremony, produced by `TrustedSetupOutput::contribute`.
+///
+/// Records `delta` before and after the contribution, along with a Schnorr proof of knowledge of the secret `k` with
+/// `delta_after = k * delta_before`. This shows the contributor built on the previous setup rather than replacing it
+/// with one whose secrets they know.
+#[cfg(feature = "transcript")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contribution<C: Pairing> {
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    delta_before: C::G1,
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    delta_after: C::G1,
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    commitment: C::G1,
+    #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+    response: C::ScalarField,
+}
+
+#[cfg(feature = "transcript")]
+impl<C: Pairing> Contribution<C> {
+    /// The Fiat-Shamir challenge for the proof of knowledge.
+    fn challenge(
+        delta_before: &C::G1,
+        delta_after: &C::G1,
+        commitment: &C::G1,
+    ) -> Result<C::ScalarField, Report> {
+        let mut transcript = Transcript::<C>::new(b"groth16-rs setup contribution");
+        transcript.append_g1(delta_before)?;
+        transcript.append_g1(delta_after)?;
+        transcript.append_g1(commitment)?;
+        transcript.challenge_scalar()
+    }
+
+    /// Check the proof of knowledge of `k`.
+    fn verify(&self) -> Result<bool, Report> {
+        let challenge = Self::challenge(&self.delta_before, &self.delta_after, &self.commitment)
+            .context("Deriving contribution challenge")?;
+        Ok(self.delta_before * self.response == self.commitment + self.delta_after * challenge)
+    }
+}
+
+/// Updatable setup ceremonies. Each party multiplies `delta` by a fresh secret, so the setup can't be forged as
+/// long as at least one party discards their secret, even if the initial setup's secrets are known.
+/// `alpha`, `beta`, `gamma`, and `tau` aren't updated, so this is the circuit specific "phase 2" of a ceremony.
+#[cfg(feature = "transcript")]
+impl<C: Pairing> TrustedSetupOutput<C> {
+    /// Re-randomize `delta` by a fresh secret `k` from `rng`, giving the updated setup and a publicly verifiable
+    /// record of the update. `delta` is multiplied by `k`, and everything divided by `delta` is divided by `k`.
+    pub fn contribute<R: rand::Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(TrustedSetupOutput<C>, Contribution<C>), Report> {
+        let k: C::ScalarField = loop {
+            let k = rand_scalar(rng);
+            if k != C::ScalarField::default() {
+                break k;
+            }
+        };
+        let k_inverse = C::ScalarField::from(1) / k;
+
+        let public = self.qap.public_witness.len();
+        let updated = TrustedSetupOutput {
+            delta_1: self.delta_1 * k,
+            delta_2: self.delta_2 * k,
+            zero_polynomial_srs: self
+                .zero_polynomial_srs
+                .iter()
+                .map(|x| *x * k_inverse)
+                .collect(),
+            zero_polynomial_srs_2: self
+                .zero_polynomial_srs_2
+                .iter()
+                .map(|x| *x * k_inverse)
+                .collect(),
+            psi_polynomials: self
+                .psi_polynomials
+                .iter()
+                .enumerate()
+                .map(|(i, x)| if i < public { *x } else { *x * k_inverse })
+                .collect(),
+            ..self.clone()
+        };
+
+        let nonce: C::ScalarField = rand_scalar(rng);
+        let commitment = self.delta_1 * nonce;
+        let challenge = Contribution::<C>::challenge(&self.delta_1, &updated.delta_1, &commitment)
+            .context("Deriving contribution challenge")?;
+        let contribution = Contribution {
+            delta_before: self.delta_1,
+            delta_after: updated.delta_1,
+            commitment,
+            response: nonce + challenge * k,
+        };
+
+        Ok((updated, contribution))
+    }
+
+    /// Check this setup was derived from `initial` by the chain of `contributions`, in order.
+    /// Returns `Ok(false)` if any contribution's proof of knowledge fails, the chain doesn't link `initial` to this
+    /// setup, or this setup isn't consistent with `initial` updated by some secret.
+    pub fn verify_contribution(
+        &self,
+        initial: &TrustedSetupOutput<C>,
+        contributions: &[Contribution<C>],
+    ) -> Result<bool, Report> {
+        let mut delta = initial.delta_1;
+        for contribution in contributions {
+            if contribution.delta_before != delta
+                || !contribution
+                    .verify()
+                    .context("Verifying contribution proof of knowledge")?
+            {
+                return Ok(false);
+            }
+            delta = contribution.delta_after;
+        }
+        if delta != self.delta_1 {
+            return Ok(false);
+        }
+
+        let public = initial.qap.public_witness.len();
+        let unchanged = self.qap == initial.qap
+            && self.alpha == initial.alpha
+            && self.beta_1 == initial.beta_1
+            && self.beta_2 == initial.beta_2
+            && self.gamma == initial.gamma
+            && self.group_1_srs == initial.group_1_srs
+            && self.group_2_srs == initial.group_2_srs
+            && self.u_tau == initial.u_tau
+            && self.v_tau_1 == initial.v_tau_1
+            && self.v_tau_2 == initial.v_tau_2
+            && self.zero_polynomial_srs.len() == initial.zero_polynomial_srs.len()
+            && self.zero_polynomial_srs_2.len() == initial.zero_polynomial_srs_2.len()
+            && self.psi_polynomials.len() == initial.psi_polynomials.len()
+            && self.psi_polynomials[..public.min(self.psi_polynomials.len())]
+                == initial.psi_polynomials[..public.min(initial.psi_polynomials.len())];
+        if !unchanged {
+            return Ok(false);
+        }
+
+        // delta is the same in both groups
+        if C::pairing(self.delta_1, C::G2::generator())
+            != C::pairing(C::G1::generator(), self.delta_2)
+        {
+            return Ok(false);
+        }
+
+        // Everything divided by delta was divided by the same secret, checked all at once with a random linear
+        // combination of `e(x_after, delta_after) == e(x_before, delta_before)`
+        let mut rng = rand::rng();
+        let (before, after): (Vec<_>, Vec<_>) = zip(
+            initial
+                .zero_polynomial_srs
+                .iter()
+                .chain(initial.psi_polynomials.iter().skip(public)),
+            self.zero_polynomial_srs
+                .iter()
+                .chain(self.psi_polynomials.iter().skip(public)),
+        )
+        .map(|(before, after)| {
+            let r: C::ScalarField = rand_scalar(&mut rng);
+            (*before * r, *after * r)
+        })
+        .unzip();
+        let before: C::G1 = before.into_iter().sum();
+        let after: C::G1 = after.into_iter().sum();
+        let (before_2, after_2): (Vec<_>, Vec<_>) =
+            zip(&initial.zero_polynomial_srs_2, &self.zero_polynomial_srs_2)
+                .map(|(before, after)| {
+                    let r: C::ScalarField = rand_scalar(&mut rng);
+                    (*before * r, *after * r)
+                })
+                .unzip();
+        let before_2: C::G2 = before_2.into_iter().sum();
+        let after_2: C::G2 = after_2.into_iter().sum();
+        // Pairing with the identity panics on some curves, so skip the group 2 check when there's nothing to check
+        Ok(
+            C::pairing(after, self.delta_2) == C::pairing(before, initial.delta_2)
+                && (self.zero_polynomial_srs_2.is_empty()
+                    || C::pairing(self.delta_1, after_2) == C::pairing(initial.delta_1, before_2)),
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::circuits::{QAP, R1CS};
-    use crate::groth16::TrustedSetupOutput;
+    use crate::error::Groth16Error;
+    use crate::groth16::{
+        ConfiguredSrs, Proof, SetupConfig, TrustedSetupOutput, VerifyingKeyAffine,
+    };
     use crate::helpers::rand_scalar;
     use crate::polynomial::Polynomial;
     use ark_ec::PrimeGroup;
-    use ark_ec::pairing::{MillerLoopOutput, Pairing, PairingOutput};
+    use ark_ec::pairing::{MillerLoopOutput, Pairing};
     use ark_ff::{MontConfig, PrimeField};
     use ark_mnt6_753::MNT6_753;
     use log::debug;
@@ -365,159 +2049,1671 @@ mod tests {
     use rootcause::{Report, report};
     use std::iter::zip;
 
-    fn init() {
-        let _ = env_logger::builder().is_test(true).try_init();
+    fn init() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    type Field = ark_mnt6_753::Fr;
+
+    fn r1cs_matrices() -> (Vec<Vec<i32>>, Vec<Vec<i32>>, Vec<Vec<i32>>) {
+        let l = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+        ];
+
+        let r = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+        ];
+
+        let o = vec![
+            vec![0, 0, 0],
+            vec![0, 0, 1],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+        ];
+
+        (l, r, o)
+    }
+
+    #[test]
+    fn groth16() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+
+        debug!("Matrices initialised");
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+
+        debug!("R1CS initialised: {:?}", r1cs);
+        let qap = QAP::from(r1cs.clone());
+
+        debug!("QAP derived");
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        debug!("Trusted Setup complete");
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let r = x * y * z * u;
+        let v1 = x * y;
+        let v2 = z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
+
+        debug!("Generating proof for witness {:?}", w);
+        assert_eq!(r, x * y * z * u);
+        assert!(r1cs.verify(&w)?);
+        assert!(qap.verify(&w));
+        let proof = trusted_setup.prove(&w)?;
+
+        debug!("Proof generated");
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
+        Ok(())
+    }
+
+    #[test]
+    fn groth16_bls12_381() -> Result<(), Report> {
+        use ark_bls12_381::{Bls12_381, Fr};
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let mut rng = rand::rng();
+        let [x, y, z, u] = [(); 4].map(|_| Fr::from(rng.random_range(0..1000)));
+        let r1cs: R1CS<Fr> = R1CS::new(l, r, o, vec![Fr::from(1), x * y * z * u]);
+        let qap = QAP::from(r1cs.clone());
+        let trusted_setup: TrustedSetupOutput<Bls12_381> = TrustedSetupOutput::new(qap.clone())?;
+        trusted_setup.sanity_check()?;
+
+        let w = vec![Fr::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+        assert!(r1cs.verify(&w)?);
+        assert!(qap.verify(&w));
+        let proof = trusted_setup.prove(&w)?;
+
+        let public_witness = &w[..2];
+        assert!(proof.verify(&trusted_setup, public_witness)?);
+        assert!(proof.verify_with_key(&trusted_setup.verifying_key_affine(), public_witness)?);
+        assert!(!proof.verify(&trusted_setup, &[Fr::from(1), x * y * z * u + Fr::from(1)])?);
+
+        let proof = Proof::<Bls12_381>::from_bytes(&proof.to_bytes())?;
+        let trusted_setup = TrustedSetupOutput::<Bls12_381>::from_bytes(&trusted_setup.to_bytes())?;
+        assert!(proof.verify(&trusted_setup, public_witness)?);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_witness_length_errors() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+
+        debug!("Matrices initialised");
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+
+        debug!("R1CS initialised: {:?}", r1cs);
+        let qap = QAP::from(r1cs.clone());
+
+        debug!("QAP derived");
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        debug!("Trusted Setup complete");
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let r = x * y * z * u;
+        let v1 = x * y;
+        let v2 = z * u;
+        // Missing y
+        let w = vec![Field::from(1), r, x, z, u, v1, v2];
+
+        debug!("Generating proof for witness {:?}", w);
+        assert_eq!(r, x * y * z * u);
+        assert!(!r1cs.verify(&w)?);
+        assert!(!qap.verify(&w));
+        let err = trusted_setup.prove(&w).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::WitnessLength {
+                expected: 8,
+                got: 7
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn groth16_public_witness() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+
+        debug!("Matrices initialised");
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let r = x * y * z * u;
+        let v1 = x * y;
+        let v2 = z * u;
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+
+        debug!("R1CS initialised: {:?}", r1cs);
+        let qap = QAP::from(r1cs.clone());
+
+        debug!("QAP derived");
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        debug!("Trusted Setup complete");
+        let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
+
+        debug!("Generating proof for witness {:?}", w);
+        assert_eq!(r, x * y * z * u);
+        assert!(r1cs.verify(&w)?);
+        assert!(qap.verify(&w));
+        let proof = trusted_setup.prove(&w)?;
+
+        debug!("Proof generated");
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_ct_matches_verify() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let out = x * y * z * u;
+        let w = vec![Field::from(1), out, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![Field::from(1), out]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let proof = trusted_setup.prove(&w)?;
+
+        let public_witness = vec![Field::from(1), out];
+        assert!(proof.verify(&trusted_setup, &public_witness)?);
+        assert!(
+            proof
+                .verify_ct(&trusted_setup, &public_witness)?
+                .into_bool()
+        );
+
+        let public_witness = vec![Field::from(1), out + Field::from(1)];
+        assert!(!proof.verify(&trusted_setup, &public_witness)?);
+        assert!(
+            !proof
+                .verify_ct(&trusted_setup, &public_witness)?
+                .into_bool()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn public_input_commitment_matches_verify() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let r = x * y * z * u;
+        let v1 = x * y;
+        let v2 = z * u;
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let qap = QAP::from(r1cs);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
+        let proof = trusted_setup.prove(&w)?;
+
+        let commitment = trusted_setup.public_input_commitment(&qap.public_witness)?;
+        assert_eq!(
+            commitment,
+            trusted_setup.psi_polynomials[0] + trusted_setup.psi_polynomials[1] * r
+        );
+
+        // Rebuild the verification equation from the exposed commitment
+        let lhs = MNT6_753::pairing(proof.a, proof.b);
+        let rhs = MNT6_753::pairing(trusted_setup.alpha, trusted_setup.beta_2)
+            + MNT6_753::pairing(commitment, trusted_setup.gamma)
+            + MNT6_753::pairing(proof.c, trusted_setup.delta_2);
+        assert_eq!(lhs, rhs);
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
+
+        assert_eq!(
+            trusted_setup.public_input_commitment(&[])?,
+            <MNT6_753 as Pairing>::G1::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_setup_fails_verification() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let mut trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let proof = trusted_setup.prove(&w)?;
+        let public_witness = vec![Field::from(1), r];
+        assert!(proof.verify(&trusted_setup, &public_witness)?);
+
+        trusted_setup.psi_polynomials.truncate(1);
+        let err = proof.verify(&trusted_setup, &public_witness).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::SrsTooSmall { needed: 2, have: 1 })
+        );
+        assert!(proof.verify_ct(&trusted_setup, &public_witness).is_err());
+        assert!(!proof.verify_unwrap(&trusted_setup, &public_witness));
+        Ok(())
+    }
+
+    #[test]
+    fn srs_lengths_checked_at_setup() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        assert_eq!(qap.degree(), qap.domain.len());
+
+        // At the boundary, t(x) shifted by the highest power fills the group 1 SRS exactly
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap.clone())?;
+        assert_eq!(trusted_setup.group_1_srs.len(), 2 * qap.degree() - 1);
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = trusted_setup.prove(&w)?;
+        assert!(proof.verify(&trusted_setup, &[Field::from(1), Field::from(120)])?);
+
+        // Padding the polynomials with trailing zeros doesn't inflate the SRSs
+        let mut padded = qap.clone();
+        for poly in padded.u.iter_mut() {
+            *poly = Polynomial::new([poly.coefficients(), &[Field::from(0); 2]].concat());
+        }
+        let padded_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(padded)?;
+        assert_eq!(padded_setup.group_1_srs.len(), 2 * qap.degree() - 1);
+        assert_eq!(padded_setup.group_2_srs.len(), qap.degree());
+
+        // With a point missing from the domain, the polynomials have too many coefficients for the group 2 SRS
+        let mut shorter = qap;
+        shorter.domain.pop();
+        let err = TrustedSetupOutput::<MNT6_753>::new(shorter).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::SrsTooSmall { needed: 3, have: 2 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sanity_check_catches_corruption() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), Field::from(120)]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        trusted_setup.sanity_check()?;
+
+        let mut corrupted = trusted_setup.clone();
+        corrupted.beta_1 += <MNT6_753 as Pairing>::G1::generator();
+        let err = corrupted.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup { field: "beta_2" })
+        );
+
+        let mut corrupted = trusted_setup.clone();
+        corrupted.group_1_srs[2] += <MNT6_753 as Pairing>::G1::generator();
+        let err = corrupted.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup {
+                field: "group_1_srs"
+            })
+        );
+
+        let mut corrupted = trusted_setup.clone();
+        corrupted.v_tau_2[1] += <MNT6_753 as Pairing>::G2::generator();
+        let err = corrupted.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup { field: "v_tau_2" })
+        );
+
+        let mut corrupted = trusted_setup.clone();
+        corrupted.qap.domain.clear();
+        let err = corrupted.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup { field: "qap" })
+        );
+
+        let mut corrupted = trusted_setup;
+        corrupted.group_2_srs.pop();
+        let err = corrupted.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup {
+                field: "group_2_srs"
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn identity_proof_elements_rejected() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let public_witness = vec![Field::from(1), r];
+
+        let mut proof = trusted_setup.prove(&w)?;
+        proof.a = Default::default();
+        let err = proof.verify(&trusted_setup, &public_witness).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::MalformedProof { element: "A" })
+        );
+        let err = proof
+            .verify_with_key(&trusted_setup.verifying_key_affine(), &public_witness)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::MalformedProof { element: "A" })
+        );
+        assert!(!proof.verify_unwrap(&trusted_setup, &public_witness));
+
+        let mut proof = trusted_setup.prove(&w)?;
+        proof.c = Default::default();
+        let err = proof
+            .verify_ct(&trusted_setup, &public_witness)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::MalformedProof { element: "C" })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn no_public_inputs_verify_uniformly() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, Vec::<i32>::new());
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        assert_eq!(trusted_setup.num_public_inputs(), 0);
+
+        let proof = trusted_setup.prove(&w)?;
+        let terms = proof.pairing_terms(&trusted_setup, &[], None)?;
+        assert_eq!(terms.x1_gamma, Default::default());
+        assert!(proof.verify(&trusted_setup, &[])?);
+        assert!(proof.verify_ct(&trusted_setup, &[])?.into_bool());
+        assert!(proof.verify_with_key(&trusted_setup.verifying_key_affine(), &[])?);
+        assert!(proof.verify_debug(&trusted_setup, &[])?);
+        Ok(())
+    }
+
+    #[test]
+    fn multi_pairing_agrees_with_pairwise() -> Result<(), Report> {
+        use ark_ff::Zero;
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![1, 120]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let key = trusted_setup.verifying_key_affine();
+        let alpha_beta = trusted_setup.alpha_beta();
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = trusted_setup.prove(&w)?;
+
+        for (public_witness, valid) in [
+            ([Field::from(1), Field::from(120)], true),
+            ([Field::from(1), Field::from(121)], false),
+        ] {
+            let (lhs, rhs) = proof
+                .pairing_terms(&trusted_setup, &public_witness, None)?
+                .sides();
+            assert_eq!(lhs == rhs, valid);
+
+            let check = proof.pairing_check(&trusted_setup, &public_witness, None)?;
+            assert_eq!(check.is_zero(), valid);
+            assert_eq!(
+                check,
+                proof.pairing_check(&trusted_setup, &public_witness, Some(alpha_beta))?
+            );
+            assert_eq!(proof.verify(&trusted_setup, &public_witness)?, valid);
+            assert_eq!(
+                proof
+                    .verify_ct(&trusted_setup, &public_witness)?
+                    .into_bool(),
+                valid
+            );
+            assert_eq!(proof.verify_with_key(&key, &public_witness)?, valid);
+            assert_eq!(
+                proof.verify_with_alpha_beta(&trusted_setup, &public_witness, alpha_beta, true)?,
+                valid
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn constant_only_public_input() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![1]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs.clone()))?;
+        assert_eq!(trusted_setup.num_public_inputs(), 1);
+
+        // Every wire but the constant is private, so any satisfying witness verifies against just `[1]`
+        for [x, y, z, u] in [[2, 3, 4, 5], [7, 1, 9, 6]] {
+            let [x, y, z, u] = [x, y, z, u].map(Field::from);
+            let r = x * y * z * u;
+            let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+            assert!(r1cs.verify(&w)?);
+
+            let proof = trusted_setup.prove(&w)?;
+            assert!(proof.verify(&trusted_setup, &[Field::from(1)])?);
+            assert!(
+                proof.verify_with_key(&trusted_setup.verifying_key_affine(), &[Field::from(1)])?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn free_function_flow() -> Result<(), Report> {
+        use crate::groth16::{prove, setup, verify};
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![1, 120]);
+        let proving_key = setup::<MNT6_753>(QAP::from(r1cs))?;
+        let verifying_key = proving_key.verifying_key_affine();
+
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = prove(&proving_key, &w)?;
+        assert!(verify(
+            &verifying_key,
+            &proof,
+            &[Field::from(1), Field::from(120)]
+        )?);
+        assert!(!verify(
+            &verifying_key,
+            &proof,
+            &[Field::from(1), Field::from(121)]
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn rerandomized_proof_verifies() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let public_witness = [Field::from(1), r];
+
+        let proof = trusted_setup.prove(&w)?;
+        let rerandomized = proof.rerandomize(&trusted_setup, &mut rand::rng());
+        assert_ne!(rerandomized.a, proof.a);
+        assert_ne!(rerandomized.b, proof.b);
+        assert_ne!(rerandomized.c, proof.c);
+        assert!(rerandomized.verify(&trusted_setup, &public_witness)?);
+        assert!(!rerandomized.verify(&trusted_setup, &[Field::from(1), r + Field::from(1)])?);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_witness_and_empty_qap_errors() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let mut trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs.clone()))?;
+
+        let err = trusted_setup.prove(&[]).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::EmptyWitness));
+        let err = r1cs.verify(&[]).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::EmptyWitness));
+
+        // Every wire public leaves the private psi sum empty, which is fine
+        let all_public: R1CS<Field> = R1CS::new(r1cs.L, r1cs.R, r1cs.O, w.clone());
+        let all_public_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(all_public))?;
+        assert!(all_public_setup.prove(&w)?.verify(&all_public_setup, &w)?);
+
+        let mut qap = trusted_setup.qap.clone();
+        qap.u.clear();
+        qap.v.clear();
+        qap.w.clear();
+        qap.public_witness.clear();
+        // An empty domain means no constraints, so this can only come from a tampered setup
+        let mut empty_domain = qap.clone();
+        empty_domain.domain.clear();
+        let err = TrustedSetupOutput::<ark_mnt6_753::MNT6_753>::new(empty_domain).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::EmptyCircuit));
+        trusted_setup.qap = qap;
+        let err = trusted_setup.prove(&w).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::NoWires));
+
+        let no_wires: R1CS<Field> = R1CS::new(
+            Vec::<Vec<i32>>::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::<i32>::new(),
+        );
+        let err = no_wires.verify(&w).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::NoWires));
+        Ok(())
+    }
+
+    #[test]
+    fn public_witness_length_checked() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let key = trusted_setup.verifying_key_affine();
+        assert_eq!(trusted_setup.num_public_inputs(), 2);
+        assert_eq!(key.num_public_inputs(), 2);
+
+        let proof = trusted_setup.prove(&w)?;
+        assert!(proof.verify(&trusted_setup, &[Field::from(1), r])?);
+        for public_witness in [vec![Field::from(1)], vec![Field::from(1), r, x]] {
+            let expected = Groth16Error::PublicWitnessLength {
+                expected: 2,
+                got: public_witness.len(),
+            };
+            let err = proof.verify(&trusted_setup, &public_witness).unwrap_err();
+            assert_eq!(Groth16Error::find(&err), Some(&expected));
+            let err = proof.verify_with_key(&key, &public_witness).unwrap_err();
+            assert_eq!(Groth16Error::find(&err), Some(&expected));
+            let err = proof
+                .verify_ct(&trusted_setup, &public_witness)
+                .unwrap_err();
+            assert_eq!(Groth16Error::find(&err), Some(&expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn verify_debug_attaches_both_sides() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+        let public_witness = [Field::from(1), r];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, public_witness.to_vec());
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let mut proof = trusted_setup.prove(&w)?;
+        assert!(proof.verify_debug(&trusted_setup, &public_witness)?);
+
+        proof.c += <MNT6_753 as Pairing>::G1::generator();
+        let err = proof
+            .verify_debug(&trusted_setup, &public_witness)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::VerificationFailed)
+        );
+        let attachments: Vec<&String> = err
+            .iter_reports()
+            .flat_map(|report| report.attachments().iter())
+            .filter_map(|attachment| attachment.downcast_inner::<String>())
+            .collect();
+        let side = |prefix: &str| {
+            attachments
+                .iter()
+                .find_map(|attachment| attachment.strip_prefix(prefix))
+                .expect("Side of the equation should be attached")
+        };
+        assert_ne!(side("LHS: "), side("RHS: "));
+        for term in [
+            "e(A, B): ",
+            "e(alpha, beta): ",
+            "e(x, gamma): ",
+            "e(C, delta): ",
+        ] {
+            side(term);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_alpha_beta() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let proof = trusted_setup.prove(&w)?;
+        let public_witness = vec![Field::from(1), r];
+
+        let alpha_beta = trusted_setup.alpha_beta();
+        assert!(proof.verify_with_alpha_beta(
+            &trusted_setup,
+            &public_witness,
+            alpha_beta,
+            false
+        )?);
+        assert!(proof.verify_with_alpha_beta(&trusted_setup, &public_witness, alpha_beta, true)?);
+
+        let wrong = alpha_beta + alpha_beta;
+        assert!(!proof.verify_with_alpha_beta(&trusted_setup, &public_witness, wrong, false)?);
+        let err = proof
+            .verify_with_alpha_beta(&trusted_setup, &public_witness, wrong, true)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::AlphaBetaMismatch)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strict_mode_rejects_zero_public_witness() -> Result<(), Report> {
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![0, 0]);
+        let qap = QAP::from(r1cs.clone());
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        // x = 0 forces every public input to zero
+        let mut rng = rand::rng();
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let zero = Field::from(0);
+        let w = vec![zero, zero, zero, y, z, u, zero, z * u];
+
+        assert!(r1cs.verify(&w)?);
+        let proof = trusted_setup.prove(&w)?;
+
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
+        assert!(proof.verify_with_mode(&trusted_setup, &qap.public_witness, false)?);
+        assert!(!proof.verify_with_mode(&trusted_setup, &qap.public_witness, true)?);
+        Ok(())
+    }
+
+    #[test]
+    fn cached_evaluations_match_recompute() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let qap = QAP::from(r1cs);
+        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+
+        let mut rng = rand::rng();
+        let w: Vec<Field> = (0..qap.u.len()).map(|_| rand_scalar(&mut rng)).collect();
+
+        let recompute_g1 = |polynomials: &Vec<Polynomial<Field>>| -> Result<_, Report> {
+            Ok(zip(polynomials, &w)
+                .map(|(p, a_i)| Ok(p.evaluate_over_srs(&trusted_setup.group_1_srs)? * a_i))
+                .collect::<Result<Vec<_>, Report>>()?
+                .into_iter()
+                .reduce(std::ops::Add::add)
+                .ok_or(report!("Empty witness"))?)
+        };
+        let recompute_v_2 = zip(&qap.v, &w)
+            .map(|(p, a_i)| Ok(p.evaluate_over_srs(&trusted_setup.group_2_srs)? * a_i))
+            .collect::<Result<Vec<_>, Report>>()?
+            .into_iter()
+            .reduce(std::ops::Add::add)
+            .ok_or(report!("Empty witness"))?;
+
+        assert_eq!(trusted_setup.evaluate_u(&w), recompute_g1(&qap.u)?);
+        assert_eq!(trusted_setup.evaluate_v_1(&w), recompute_g1(&qap.v)?);
+        assert_eq!(trusted_setup.evaluate_w(&w)?, recompute_g1(&qap.w)?);
+        assert_eq!(trusted_setup.evaluate_v(&w), recompute_v_2);
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_proofs_reuse_cached_evaluations() -> Result<(), Report> {
+        use std::time::Instant;
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+        let public_witness = vec![Field::from(1), r];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, public_witness.clone());
+        let qap = QAP::from(r1cs);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap.clone())?;
+
+        let first = trusted_setup.prove(&w)?;
+        let second = trusted_setup.prove(&w)?;
+        assert_ne!(first, second);
+        assert!(first.verify(&trusted_setup, &public_witness)?);
+        assert!(second.verify(&trusted_setup, &public_witness)?);
+
+        // Compare the per proof cost of the cached evaluations against evaluating every polynomial over the SRS
+        let start = Instant::now();
+        let cached = (
+            trusted_setup.evaluate_u(&w),
+            trusted_setup.evaluate_v_1(&w),
+            trusted_setup.evaluate_v(&w),
+        );
+        let cached_time = start.elapsed();
+        let start = Instant::now();
+        let evaluate_g1 = |polynomials: &Vec<Polynomial<Field>>| -> Result<_, Report> {
+            zip(polynomials, &w)
+                .map(|(p, a_i)| Ok(p.evaluate_over_srs(&trusted_setup.group_1_srs)? * a_i))
+                .sum::<Result<<MNT6_753 as Pairing>::G1, Report>>()
+        };
+        let recomputed = (
+            evaluate_g1(&qap.u)?,
+            evaluate_g1(&qap.v)?,
+            zip(&qap.v, &w)
+                .map(|(p, a_i)| Ok(p.evaluate_over_srs(&trusted_setup.group_2_srs)? * a_i))
+                .sum::<Result<<MNT6_753 as Pairing>::G2, Report>>()?,
+        );
+        let recomputed_time = start.elapsed();
+        assert_eq!(cached, recomputed);
+        // w(x) isn't cached since proving uses the psi polynomials instead, but its witness-weighted sum must agree too
+        assert_eq!(trusted_setup.evaluate_w(&w)?, evaluate_g1(&qap.w)?);
+        debug!("Per proof evaluation: cached {cached_time:?}, recomputed {recomputed_time:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn over_long_public_witness_errors() {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1; 9]);
+        let err = TrustedSetupOutput::<MNT6_753>::new(QAP::from(r1cs)).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::PublicWitnessTooLong {
+                public: 9,
+                wires: 8
+            })
+        );
+    }
+
+    #[test]
+    fn qap_quotient_matches_setup() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap.clone())?;
+
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let h = qap.quotient(&w)?;
+        assert_eq!(h, trusted_setup.quotient_polynomial(&w)?);
+        assert!(h.degree() <= qap.degree() - 2);
+
+        let mut bad = w.clone();
+        bad[1] += Field::from(1);
+        let err = qap.quotient(&bad).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+
+        // The setup exposes the same computation, and a failure shows the operands of the division
+        let err = trusted_setup.quotient_polynomial(&bad).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+        let attachments: Vec<&String> = err
+            .iter_reports()
+            .flat_map(|report| report.attachments().iter())
+            .filter_map(|attachment| attachment.downcast_inner::<String>())
+            .collect();
+        let numerator = qap
+            .u
+            .iter()
+            .zip(&bad)
+            .map(|(u_i, a_i)| u_i * *a_i)
+            .sum::<Polynomial<Field>>()
+            * qap
+                .v
+                .iter()
+                .zip(&bad)
+                .map(|(v_i, a_i)| v_i * *a_i)
+                .sum::<Polynomial<Field>>()
+            - qap
+                .w
+                .iter()
+                .zip(&bad)
+                .map(|(w_i, a_i)| w_i * *a_i)
+                .sum::<Polynomial<Field>>();
+        assert!(attachments.contains(&&format!("A(x) * B(x) - C(x): {:?}", numerator)));
+        assert!(attachments.contains(&&format!("t(x): {:?}", Polynomial::vanishing(&qap.domain))));
+        let err = qap.quotient(&w[1..]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::WitnessLength {
+                expected: 8,
+                got: 7
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_circuit_errors() {
+        init();
+
+        let r1cs: R1CS<Field> = R1CS::new(
+            Vec::<Vec<i32>>::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::<i32>::new(),
+        );
+        let result = TrustedSetupOutput::<MNT6_753>::new(QAP::from(r1cs));
+        assert!(format!("{:?}", result.unwrap_err()).contains("Circuit is empty"));
+
+        // Wires but no constraints
+        let r1cs: R1CS<Field> = R1CS::new(
+            vec![Vec::<i32>::new(); 3],
+            vec![Vec::new(); 3],
+            vec![Vec::new(); 3],
+            Vec::<i32>::new(),
+        );
+        let result = TrustedSetupOutput::<MNT6_753>::new(QAP::from(r1cs));
+        let err = result.unwrap_err();
+        assert!(format!("{:?}", err).contains("Circuit is empty"));
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::EmptyCircuit));
+    }
+
+    #[test]
+    fn roots_of_unity_domain() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1]);
+        let domain = QAP::roots_of_unity_domain(r1cs.num_constraints())?;
+        assert_eq!(domain.len(), 4);
+
+        let integer_qap = QAP::from(r1cs.clone());
+        let roots_qap = QAP::from_over_domain(r1cs, &domain)?;
+        assert_eq!(roots_qap.domain, domain);
+
+        // Over a multiplicative subgroup of size n the vanishing polynomial is x^n - 1
+        assert_eq!(
+            TrustedSetupOutput::<MNT6_753>::t(&roots_qap.domain)?,
+            Polynomial::from(vec![-1, 0, 0, 0, 1])
+        );
+
+        let mut rng = rand::rng();
+        let x = Field::from(rng.random_range(0..1000));
+        let y = Field::from(rng.random_range(0..1000));
+        let z = Field::from(rng.random_range(0..1000));
+        let u = Field::from(rng.random_range(0..1000));
+        let w = vec![Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+
+        for qap in [integer_qap, roots_qap] {
+            assert!(qap.verify_exact(&w));
+            let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap)?;
+            let proof = trusted_setup.prove(&w)?;
+            assert!(proof.verify(&trusted_setup, &[Field::from(1)])?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn zeroized_setup_succeeds() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = trusted_setup.prove(&w)?;
+        assert!(proof.verify(&trusted_setup, &vec![])?);
+        Ok(())
+    }
+
+    #[test]
+    fn setup_config_gives_same_setup() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        let secrets = [2, 3, 5, 7, 11].map(Field::from);
+        let setup = |config: &SetupConfig| {
+            TrustedSetupOutput::<MNT6_753>::new_with_secrets_and_config(
+                qap.clone(),
+                secrets[0],
+                secrets[1],
+                secrets[2],
+                secrets[3],
+                secrets[4],
+                config,
+            )
+        };
+
+        let default = setup(&SetupConfig::default())?;
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = default.prove(&w)?;
+        let public_witness = vec![Field::from(1), Field::from(120)];
+
+        for config in [
+            SetupConfig {
+                window: Some(5),
+                ..SetupConfig::default()
+            },
+            SetupConfig {
+                window: Some(3),
+                threads: Some(2),
+                precompute_affine: true,
+                ..SetupConfig::default()
+            },
+        ] {
+            let configured = setup(&config)?;
+            assert_eq!(configured, default);
+            assert!(proof.verify(&configured, &public_witness)?);
+        }
+
+        let configured = TrustedSetupOutput::<MNT6_753>::new_with_config(
+            qap,
+            &SetupConfig {
+                window: Some(4),
+                threads: Some(2),
+                precompute_affine: true,
+                ..SetupConfig::default()
+            },
+        )?;
+        let proof = configured.prove(&w)?;
+        assert!(proof.verify(&configured, &public_witness)?);
+        Ok(())
+    }
+
+    #[test]
+    fn zero_polynomial_srs_in_group_2() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        let default: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap.clone())?;
+        assert!(default.zero_polynomial_srs_2().is_empty());
+
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new_with_config(
+            qap,
+            &SetupConfig {
+                zero_polynomial_srs_2: true,
+                ..SetupConfig::default()
+            },
+        )?;
+        let (srs_1, srs_2) = (
+            trusted_setup.zero_polynomial_srs(),
+            trusted_setup.zero_polynomial_srs_2(),
+        );
+        assert_eq!(srs_1.len(), srs_2.len());
+        assert!(!srs_1.is_empty());
+        for (z_1, z_2) in zip(srs_1, srs_2) {
+            assert_eq!(
+                MNT6_753::pairing(*z_1, <MNT6_753 as Pairing>::G2::generator()),
+                MNT6_753::pairing(<MNT6_753 as Pairing>::G1::generator(), *z_2)
+            );
+        }
+        trusted_setup.sanity_check()?;
+        assert_eq!(
+            TrustedSetupOutput::deserialize_deduplicated(&trusted_setup.serialize_deduplicated())?,
+            trusted_setup
+        );
+
+        #[cfg(feature = "transcript")]
+        {
+            let (updated, contribution) = trusted_setup.contribute(&mut rand::rng())?;
+            assert!(updated.verify_contribution(&trusted_setup, &[contribution])?);
+            updated.sanity_check()?;
+        }
+
+        let mut tampered = trusted_setup.clone();
+        tampered.zero_polynomial_srs_2[0] += <MNT6_753 as Pairing>::G2::generator();
+        let err = tampered.sanity_check().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InconsistentSetup {
+                field: "zero_polynomial_srs_2"
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_setup_is_reproducible() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        let seeded = |seed| TrustedSetupOutput::<MNT6_753>::new_seeded(qap.clone(), seed);
+
+        let a = seeded([7; 32])?;
+        assert_eq!(a.to_bytes(), seeded([7; 32])?.to_bytes());
+        assert_ne!(a.to_bytes(), seeded([8; 32])?.to_bytes());
+
+        let w: Vec<Field> = [1, 120, 2, 3, 4, 5, 6, 20].map(Field::from).to_vec();
+        let proof = a.prove(&w)?;
+        assert!(proof.verify(&a, &[Field::from(1), Field::from(120)])?);
+        Ok(())
+    }
+
+    #[test]
+    fn prove_with_supplied_quotient() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new_seeded(QAP::from(r1cs), [3; 32])?;
+        let w: Vec<Field> = [1, 120, 2, 3, 4, 5, 6, 20].map(Field::from).to_vec();
+        let public_witness = [Field::from(1), Field::from(120)];
+
+        let h = trusted_setup.quotient_polynomial(&w)?;
+        let mut rng = rand::rng();
+        let proof = trusted_setup.prove_with_quotient(&w, &h, &mut rng)?;
+        assert!(proof.verify(&trusted_setup, &public_witness)?);
+
+        // The same blinding gives the same proof as computing h internally
+        use rand::SeedableRng;
+        assert_eq!(
+            trusted_setup.prove_with_quotient(&w, &h, &mut rand::rngs::StdRng::seed_from_u64(5))?,
+            trusted_setup.prove_with_rng(&w, &mut rand::rngs::StdRng::seed_from_u64(5))?
+        );
+
+        let wrong = &h + &Polynomial::from(vec![1]);
+        let proof = trusted_setup.prove_with_quotient(&w, &wrong, &mut rng)?;
+        assert!(!proof.verify(&trusted_setup, &public_witness)?);
+
+        let too_long = Polynomial::from(vec![1; trusted_setup.zero_polynomial_srs().len() + 1]);
+        let err = trusted_setup
+            .prove_with_quotient(&w, &too_long, &mut rng)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::SrsTooSmall {
+                needed: trusted_setup.zero_polynomial_srs().len() + 1,
+                have: trusted_setup.zero_polynomial_srs().len()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deduplicated_serialization() -> Result<(), Report> {
+        use ark_serialize::CanonicalSerialize;
+
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 2]);
+        let mut trusted_setup: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new(QAP::from(r1cs))?;
+
+        let round_tripped =
+            TrustedSetupOutput::deserialize_deduplicated(&trusted_setup.serialize_deduplicated())?;
+        assert_eq!(round_tripped, trusted_setup);
+
+        // Artificially repeat elements, as a degenerate circuit might
+        trusted_setup.psi_polynomials =
+            vec![trusted_setup.psi_polynomials[0]; trusted_setup.psi_polynomials.len()];
+        trusted_setup.u_tau = vec![trusted_setup.alpha; trusted_setup.u_tau.len()];
+        trusted_setup.v_tau_2 = vec![trusted_setup.gamma; trusted_setup.v_tau_2.len()];
+
+        let naive_size = trusted_setup.qap.compressed_size()
+            + [
+                vec![
+                    trusted_setup.alpha,
+                    trusted_setup.beta_1,
+                    trusted_setup.delta_1,
+                ],
+                trusted_setup.group_1_srs.clone(),
+                trusted_setup.zero_polynomial_srs.clone(),
+                trusted_setup.psi_polynomials.clone(),
+                trusted_setup.u_tau.clone(),
+                trusted_setup.v_tau_1.clone(),
+            ]
+            .concat()
+            .compressed_size()
+            + [
+                vec![
+                    trusted_setup.beta_2,
+                    trusted_setup.gamma,
+                    trusted_setup.delta_2,
+                ],
+                trusted_setup.group_2_srs.clone(),
+                trusted_setup.v_tau_2.clone(),
+            ]
+            .concat()
+            .compressed_size();
+
+        let bytes = trusted_setup.serialize_deduplicated();
+        debug!(
+            "Deduplicated {} bytes, naive {} bytes",
+            bytes.len(),
+            naive_size
+        );
+        assert!(bytes.len() < naive_size);
+        assert_eq!(
+            TrustedSetupOutput::deserialize_deduplicated(&bytes)?,
+            trusted_setup
+        );
+
+        let error =
+            TrustedSetupOutput::<MNT6_753>::deserialize_deduplicated(&bytes[..bytes.len() - 1])
+                .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::InvalidEncoding)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compact_binary_serialization() -> Result<(), Report> {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+        init();
+
+        let (l, right, o) = r1cs_matrices();
+        let x = Field::from(2);
+        let y = Field::from(3);
+        let z = Field::from(4);
+        let u = Field::from(5);
+        let r = x * y * z * u;
+        let w = vec![Field::from(1), r, x, y, z, u, x * y, z * u];
+
+        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let proof = trusted_setup.prove(&w)?;
+        let key = trusted_setup.verifying_key_affine();
+
+        let qap = trusted_setup.qap();
+        assert_eq!(&QAP::from_bytes(&qap.to_bytes())?, qap);
+        assert_eq!(
+            TrustedSetupOutput::from_bytes(&trusted_setup.to_bytes())?,
+            trusted_setup
+        );
+        assert_eq!(VerifyingKeyAffine::from_bytes(&key.to_bytes())?, key);
+        assert_eq!(Proof::from_bytes(&proof.to_bytes())?, proof);
+        assert_eq!(proof.serialized_size(), proof.to_bytes().len());
+        let mut uncompressed = vec![];
+        proof.serialize_uncompressed(&mut uncompressed)?;
+        assert_eq!(
+            Proof::<MNT6_753>::deserialize_uncompressed(uncompressed.as_slice())?,
+            proof
+        );
+        let json: Proof<MNT6_753> = serde_json::from_slice(&serde_json::to_vec(&proof)?)?;
+        assert_eq!(json, proof.clone());
+
+        for (name, binary, json) in [
+            ("QAP", qap.to_bytes(), serde_json::to_vec(qap)?),
+            (
+                "setup",
+                trusted_setup.to_bytes(),
+                serde_json::to_vec(&trusted_setup)?,
+            ),
+            ("proof", proof.to_bytes(), serde_json::to_vec(&proof)?),
+        ] {
+            debug!(
+                "{name}: binary {} bytes, JSON {} bytes",
+                binary.len(),
+                json.len()
+            );
+            assert!(binary.len() * 2 < json.len());
+        }
+
+        let bytes = proof.to_bytes();
+        let err = Proof::<MNT6_753>::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InvalidEncoding)
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "transcript")]
+    fn chained_contributions() -> Result<(), Report> {
+        use crate::groth16::Contribution;
+
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let initial: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+
+        let mut rng = rand::rng();
+        let (first, first_contribution) = initial.contribute(&mut rng)?;
+        let (second, second_contribution) = first.contribute(&mut rng)?;
+        assert_ne!(second.delta_1, initial.delta_1);
+        assert_ne!(second.delta_2, initial.delta_2);
+
+        let contributions = [first_contribution.clone(), second_contribution.clone()];
+        assert!(first.verify_contribution(&initial, &contributions[..1])?);
+        assert!(second.verify_contribution(&initial, &contributions)?);
+        assert!(second.verify_contribution(&first, &contributions[1..])?);
+        assert!(initial.verify_contribution(&initial, &[])?);
+
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let public_witness = vec![Field::from(1), Field::from(120)];
+        let proof = second.prove(&w)?;
+        assert!(proof.verify(&second, &public_witness)?);
+        assert!(!proof.verify(&initial, &public_witness)?);
+
+        // Out of order, missing, or forged contributions
+        assert!(!second.verify_contribution(
+            &initial,
+            &[second_contribution.clone(), first_contribution.clone()]
+        )?);
+        assert!(!second.verify_contribution(&initial, &contributions[1..])?);
+        let forged = Contribution {
+            response: second_contribution.response + Field::from(1),
+            ..second_contribution.clone()
+        };
+        assert!(!second.verify_contribution(&initial, &[first_contribution.clone(), forged])?);
+
+        // A setup that wasn't updated consistently
+        let mut tampered = second.clone();
+        tampered.zero_polynomial_srs[0] += initial.alpha;
+        assert!(!tampered.verify_contribution(&initial, &contributions)?);
+        let mut tampered = second.clone();
+        tampered.psi_polynomials[0] = tampered.psi_polynomials[1];
+        assert!(!tampered.verify_contribution(&initial, &contributions)?);
+        Ok(())
     }
 
-    type Field = ark_mnt6_753::Fr;
+    #[test]
+    fn reloaded_setup_proves_identically() -> Result<(), Report> {
+        use rand::SeedableRng;
 
-    fn r1cs_matrices() -> (Vec<Vec<i32>>, Vec<Vec<i32>>, Vec<Vec<i32>>) {
-        let l = vec![
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![1, 0, 0],
-            vec![0, 0, 0],
-            vec![0, 1, 0],
-            vec![0, 0, 0],
-            vec![0, 0, 1],
-            vec![0, 0, 0],
-        ];
+        init();
 
-        let r = vec![
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![1, 0, 0],
-            vec![0, 0, 0],
-            vec![0, 1, 0],
-            vec![0, 0, 0],
-            vec![0, 0, 1],
-        ];
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
 
-        let o = vec![
-            vec![0, 0, 0],
-            vec![0, 0, 1],
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![0, 0, 0],
-            vec![1, 0, 0],
-            vec![0, 1, 0],
-        ];
+        let mut bytes = vec![];
+        trusted_setup.to_writer(&mut bytes)?;
+        let reloaded = TrustedSetupOutput::<MNT6_753>::from_reader(&bytes[..])?;
+        assert_eq!(reloaded, trusted_setup);
 
-        (l, r, o)
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = trusted_setup.prove_with_rng(&w, &mut rand::rngs::StdRng::seed_from_u64(7))?;
+        let reloaded_proof =
+            reloaded.prove_with_rng(&w, &mut rand::rngs::StdRng::seed_from_u64(7))?;
+        assert_eq!(proof, reloaded_proof);
+        assert!(reloaded_proof.verify(&reloaded, &[Field::from(1), Field::from(120)])?);
+
+        assert!(TrustedSetupOutput::<MNT6_753>::from_reader(&bytes[..bytes.len() / 2]).is_err());
+        Ok(())
     }
 
     #[test]
-    fn groth16() -> Result<(), Report> {
+    fn verifying_key_affine_round_trip() -> Result<(), Report> {
+        use ark_ec::AffineRepr;
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
         init();
 
         let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let w: Vec<Field> = vec![1, 120, 2, 3, 4, 5, 6, 20]
+            .into_iter()
+            .map(Field::from)
+            .collect();
+        let proof = trusted_setup.prove(&w)?;
+        let public_witness = [Field::from(1), Field::from(120)];
+
+        let key = trusted_setup.verifying_key_affine();
+        assert_eq!(key.psi_polynomials.len(), 2);
+        let mut bytes = vec![];
+        key.serialize_compressed(&mut bytes)?;
+        let deserialized = VerifyingKeyAffine::<MNT6_753>::deserialize_compressed(&bytes[..])?;
+        assert_eq!(deserialized, key);
+
+        assert_eq!(deserialized.alpha.into_group(), trusted_setup.alpha);
+        assert_eq!(deserialized.beta_2.into_group(), trusted_setup.beta_2);
+        assert_eq!(deserialized.gamma.into_group(), trusted_setup.gamma);
+        assert_eq!(deserialized.delta_2.into_group(), trusted_setup.delta_2);
+        assert_eq!(
+            deserialized.psi_polynomials[1].into_group(),
+            trusted_setup.psi_polynomials[1]
+        );
 
-        debug!("Matrices initialised");
-        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        assert!(proof.verify_with_key(&deserialized, &public_witness)?);
+        assert!(!proof.verify_with_key(&deserialized, &[Field::from(1), Field::from(121)])?);
+        assert!(
+            proof
+                .verify_with_key(&deserialized, &[Field::from(1); 3])
+                .is_err()
+        );
+        Ok(())
+    }
 
-        debug!("R1CS initialised: {:?}", r1cs);
-        let qap = QAP::from(r1cs.clone());
+    #[test]
+    fn trusted_setup_accessors() -> Result<(), Report> {
+        init();
 
-        debug!("QAP derived");
-        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
-            TrustedSetupOutput::new(qap.clone())?;
+        fn srs_lengths<C: Pairing>(trusted_setup: &TrustedSetupOutput<C>) -> (usize, usize, usize) {
+            (
+                trusted_setup.group_1_srs().len(),
+                trusted_setup.group_2_srs().len(),
+                trusted_setup.zero_polynomial_srs().len(),
+            )
+        }
 
-        debug!("Trusted Setup complete");
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1]);
+        let qap = QAP::from(r1cs);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(qap.clone())?;
+
+        assert_eq!(trusted_setup.qap(), &qap);
+        assert_eq!(srs_lengths(&trusted_setup), (5, 3, 2));
+        assert_eq!(trusted_setup.psi_polynomials().len(), 8);
+        assert_eq!(
+            trusted_setup.group_1_srs()[0],
+            <MNT6_753 as Pairing>::G1::generator()
+        );
+        assert_eq!(
+            MNT6_753::pairing(
+                trusted_setup.beta_1(),
+                <MNT6_753 as Pairing>::G2::generator()
+            ),
+            MNT6_753::pairing(
+                <MNT6_753 as Pairing>::G1::generator(),
+                trusted_setup.beta_2()
+            )
+        );
+        assert_eq!(
+            MNT6_753::pairing(
+                trusted_setup.delta_1(),
+                <MNT6_753 as Pairing>::G2::generator()
+            ),
+            MNT6_753::pairing(
+                <MNT6_753 as Pairing>::G1::generator(),
+                trusted_setup.delta_2()
+            )
+        );
+        assert_ne!(trusted_setup.alpha(), trusted_setup.beta_1());
+        assert_ne!(trusted_setup.gamma(), trusted_setup.delta_2());
+        Ok(())
+    }
+
+    #[test]
+    fn prove_split_checks_public_witness() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
         let mut rng = rand::rng();
         let x = Field::from(rng.random_range(0..1000));
         let y = Field::from(rng.random_range(0..1000));
         let z = Field::from(rng.random_range(0..1000));
         let u = Field::from(rng.random_range(0..1000));
-        let r = x * y * z * u;
-        let v1 = x * y;
-        let v2 = z * u;
-        let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
+        let out = x * y * z * u;
 
-        debug!("Generating proof for witness {:?}", w);
-        assert_eq!(r, x * y * z * u);
-        assert!(r1cs.verify(&w)?);
-        assert!(qap.verify(&w));
-        let proof = trusted_setup.prove(&w)?;
+        let public = vec![Field::from(1), out];
+        let private = vec![x, y, z, u, x * y, z * u];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, public.clone());
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
 
-        debug!("Proof generated");
-        assert!(proof.verify(&trusted_setup, &qap.public_witness));
+        let proof = trusted_setup.prove_split(&public, &private)?;
+        assert!(proof.verify(&trusted_setup, &public)?);
+
+        let wrong_public = vec![Field::from(1), out + Field::from(1)];
+        let error = trusted_setup
+            .prove_split(&wrong_public, &private)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::PublicWitnessMismatch)
+        );
+
+        let error = trusted_setup
+            .prove_split(&public[..1], &[&public[1..], &private].concat())
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::PublicWitnessMismatch)
+        );
         Ok(())
     }
+
     #[test]
-    fn invalid_witness_length_errors() -> Result<(), Report> {
+    fn qap_accessor_matches_circuit() -> Result<(), Report> {
         init();
 
         let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 24]);
+        let num_constraints = r1cs.num_constraints();
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+
+        // A verifier only holding the serialized setup can still see the circuit's structure
+        let deserialized: TrustedSetupOutput<MNT6_753> =
+            serde_json::from_str(&serde_json::to_string(&trusted_setup)?)?;
+        assert_eq!(deserialized.qap().degree(), num_constraints);
+        assert_eq!(deserialized.qap().num_constraints(), num_constraints);
+        assert_eq!(deserialized.qap().public_witness.len(), 2);
+        assert_eq!(deserialized.qap().u.len(), 8);
+
+        // Trailing zero coefficients don't change the number of constraints
+        let mut trimmed = deserialized.qap().clone();
+        for polynomial in trimmed
+            .u
+            .iter_mut()
+            .chain(&mut trimmed.v)
+            .chain(&mut trimmed.w)
+        {
+            *polynomial = Polynomial::new(polynomial.coefficients()[..1].to_vec());
+        }
+        assert_eq!(trimmed.num_constraints(), num_constraints);
+        Ok(())
+    }
 
-        debug!("Matrices initialised");
-        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+    #[test]
+    fn single_constraint_circuit() -> Result<(), Report> {
+        init();
 
-        debug!("R1CS initialised: {:?}", r1cs);
+        // x * y = z over witness [1, x, y, z]
+        let l = vec![vec![0], vec![1], vec![0], vec![0]];
+        let r = vec![vec![0], vec![0], vec![1], vec![0]];
+        let o = vec![vec![0], vec![0], vec![0], vec![1]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
         let qap = QAP::from(r1cs.clone());
+        assert_eq!(qap.degree(), 1);
 
-        debug!("QAP derived");
         let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
             TrustedSetupOutput::new(qap.clone())?;
+        assert_eq!(trusted_setup.group_1_srs.len(), 1);
+        assert!(trusted_setup.zero_polynomial_srs.is_empty());
 
-        debug!("Trusted Setup complete");
         let mut rng = rand::rng();
         let x = Field::from(rng.random_range(0..1000));
         let y = Field::from(rng.random_range(0..1000));
-        let z = Field::from(rng.random_range(0..1000));
-        let u = Field::from(rng.random_range(0..1000));
-        let r = x * y * z * u;
-        let v1 = x * y;
-        let v2 = z * u;
-        // Missing y
-        let w = vec![Field::from(1), r, x, z, u, v1, v2];
+        let w = vec![Field::from(1), x, y, x * y];
 
-        debug!("Generating proof for witness {:?}", w);
-        assert_eq!(r, x * y * z * u);
-        assert!(!r1cs.verify(&w)?);
-        assert!(!qap.verify(&w));
-        assert!(trusted_setup.prove(&w).is_err());
+        assert!(r1cs.verify(&w)?);
+        let proof = trusted_setup.prove(&w)?;
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
 
+        // An unsatisfying witness leaves a remainder, rather than hitting the empty zero polynomial SRS
+        let bad_witness = vec![Field::from(1), x, y, x * y + Field::from(1)];
+        let err = trusted_setup.prove(&bad_witness).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
         Ok(())
     }
 
     #[test]
-    fn groth16_public_witness() -> Result<(), Report> {
+    #[cfg(feature = "transcript")]
+    fn proof_transcript_hash() -> Result<(), Report> {
         init();
 
-        let (l, right, o) = r1cs_matrices();
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let trusted_setup: TrustedSetupOutput<MNT6_753> = TrustedSetupOutput::new(QAP::from(r1cs))?;
 
-        debug!("Matrices initialised");
         let mut rng = rand::rng();
         let x = Field::from(rng.random_range(0..1000));
         let y = Field::from(rng.random_range(0..1000));
         let z = Field::from(rng.random_range(0..1000));
         let u = Field::from(rng.random_range(0..1000));
-        let r = x * y * z * u;
-        let v1 = x * y;
-        let v2 = z * u;
-
-        let r1cs: R1CS<Field> = R1CS::new(l, right, o, vec![Field::from(1), r]);
-
-        debug!("R1CS initialised: {:?}", r1cs);
-        let qap = QAP::from(r1cs.clone());
-
-        debug!("QAP derived");
-        let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
-            TrustedSetupOutput::new(qap.clone())?;
+        let w = vec![Field::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+        let proof = trusted_setup.prove(&w)?;
 
-        debug!("Trusted Setup complete");
-        let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
+        let hash = proof.transcript_hash(b"phase one");
+        assert_eq!(hash, proof.transcript_hash(b"phase one"));
+        assert_ne!(hash, proof.transcript_hash(b"phase two"));
 
-        debug!("Generating proof for witness {:?}", w);
-        assert_eq!(r, x * y * z * u);
-        assert!(r1cs.verify(&w)?);
-        assert!(qap.verify(&w));
-        let proof = trusted_setup.prove(&w)?;
+        let round_tripped: Proof<MNT6_753> = serde_json::from_str(&serde_json::to_string(&proof)?)?;
+        assert_eq!(hash, round_tripped.transcript_hash(b"phase one"));
 
-        debug!("Proof generated");
-        assert!(proof.verify(&trusted_setup, &qap.public_witness));
+        // A fresh proof of the same witness is re-randomised, so hashes differently
+        let other_proof = trusted_setup.prove(&w)?;
+        assert_ne!(hash, other_proof.transcript_hash(b"phase one"));
         Ok(())
     }
 
@@ -526,7 +3722,7 @@ mod tests {
         let mut rng = rand::rng();
         let tau: ark_mnt6_753::Fr = rand_scalar(&mut rng);
 
-        let srs = TrustedSetupOutput::<MNT6_753>::group_1_srs(16, tau);
+        let srs = TrustedSetupOutput::<MNT6_753>::generate_group_1_srs(16, tau);
 
         let polynomial: Polynomial<ark_mnt6_753::Fr> = Polynomial::from(vec![3, 5, 10, 20]);
 
@@ -565,7 +3761,7 @@ mod tests {
         let v2 = z * u;
         let w = vec![Field::from(1), r, x, y, z, u, v1, v2];
 
-        let zero_polynomial = TrustedSetupOutput::<MNT6_753>::t(qap.degree())?;
+        let zero_polynomial = TrustedSetupOutput::<MNT6_753>::t(&qap.domain)?;
 
         debug!("QAP has degree {}", qap.max_polynomial_degree());
         for i in 1..qap.max_polynomial_degree() + 1 {
@@ -579,6 +3775,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn zero_polynomial_srs_uses_t() -> Result<(), Report> {
+        use ark_ff::Field as _;
+
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![1, 120]);
+        let qap = QAP::from(r1cs);
+        let [alpha, beta, tau, gamma, delta] = [2, 3, 5, 7, 11].map(Field::from);
+        let trusted_setup: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new_with_secrets(qap.clone(), alpha, beta, tau, gamma, delta)?;
+
+        // t(tau) computed afresh as the product of (tau - d) over the domain
+        let t_tau: Field = qap.domain.iter().map(|d| tau - d).product();
+        assert_eq!(
+            TrustedSetupOutput::<MNT6_753>::t(&qap.domain)?.evaluate(&tau),
+            t_tau
+        );
+        for (i, element) in trusted_setup.zero_polynomial_srs().iter().enumerate() {
+            assert_eq!(
+                *element,
+                <MNT6_753 as Pairing>::G1::generator() * (tau.pow([i as u64]) * t_tau / delta)
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn naive() -> Result<(), Report> {
         init();
@@ -614,24 +3838,24 @@ mod tests {
         );
 
         let zero_polynomial_srs: Vec<<MNT6_753 as Pairing>::G1> =
-            TrustedSetupOutput::<MNT6_753>::zero_polynomial_srs(
-                qap.degree(),
+            TrustedSetupOutput::<MNT6_753>::generate_zero_polynomial_srs(
+                &TrustedSetupOutput::<MNT6_753>::t(&qap.domain)?,
                 (qap.degree()) - 1,
                 <MNT6_753 as Pairing>::ScalarField::from(1),
-                &trusted_setup.group_1_srs,
+                &ConfiguredSrs::new(&trusted_setup.group_1_srs, &SetupConfig::default()),
             )?;
 
         assert_eq!(zero_polynomial_srs.len(), qap.degree() - 1);
 
-        let ht = trusted_setup.calculate_zero_polynomial(&w)?;
+        let ht = trusted_setup.quotient_polynomial(&w)?;
         debug!("zero polynomial: {:?}", ht);
         let ht_tau = ht
             .evaluate_over_srs(&zero_polynomial_srs)
             .context("Evaluating ht_tau")?;
 
-        let a = trusted_setup.evaluate_u(&w).context("Evaluating u")?;
-        let b = trusted_setup.evaluate_v(&w).context("Evaluating u")?;
-        let c = trusted_setup.evaluate_w(&w).context("Evaluating w")? + ht_tau;
+        let a = trusted_setup.evaluate_u(&w);
+        let b = trusted_setup.evaluate_v(&w);
+        let c = trusted_setup.evaluate_w(&w)? + ht_tau;
 
         let lhs = MNT6_753::pairing(a, b).0;
         let rhs = MNT6_753::pairing(c, <MNT6_753 as Pairing>::G2::generator()).0;
@@ -656,12 +3880,22 @@ mod tests {
         assert_eq!(qap.degree(), 3);
 
         debug!("QAP derived");
+        let mut rng = rand::rng();
+        let alpha = rand_scalar(&mut rng);
+        let beta = rand_scalar(&mut rng);
+        let tau = rand_scalar(&mut rng);
         let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
-            TrustedSetupOutput::new(qap.clone())?;
+            TrustedSetupOutput::new_with_secrets(
+                qap.clone(),
+                alpha,
+                beta,
+                tau,
+                Field::from(1),
+                Field::from(1),
+            )?;
 
         debug!("Trusted Setup complete");
 
-        let mut rng = rand::rng();
         let x = Field::from(rng.random_range(0..1000));
         let y = Field::from(rng.random_range(0..1000));
         let z = Field::from(rng.random_range(0..1000));
@@ -676,40 +3910,19 @@ mod tests {
             qap.max_polynomial_degree()
         );
 
-        let zero_polynomial_srs: Vec<<MNT6_753 as Pairing>::G1> =
-            TrustedSetupOutput::<MNT6_753>::zero_polynomial_srs(
-                qap.degree(),
-                (qap.degree()) - 1,
-                <MNT6_753 as Pairing>::ScalarField::from(1),
-                &trusted_setup.group_1_srs,
-            )?;
-
-        assert_eq!(zero_polynomial_srs.len(), qap.degree() - 1);
+        assert_eq!(trusted_setup.zero_polynomial_srs.len(), qap.degree() - 1);
 
-        let ht = trusted_setup.calculate_zero_polynomial(&w)?;
+        let ht = trusted_setup.quotient_polynomial(&w)?;
         debug!("zero polynomial: {:?}", ht);
         let ht_tau = ht
-            .evaluate_over_srs(&zero_polynomial_srs)
+            .evaluate_over_srs(&trusted_setup.zero_polynomial_srs)
             .context("Evaluating ht_tau")?;
 
-        let alpha = rand_scalar(&mut rng);
-        let beta = rand_scalar(&mut rng);
-        let alpha_1 = <MNT6_753 as Pairing>::G1::generator() * alpha;
-        let beta_2 = <MNT6_753 as Pairing>::G2::generator() * beta;
-        let psi_polynomials = TrustedSetupOutput::<MNT6_753>::psi_polynomials(
-            &qap,
-            &trusted_setup.group_1_srs,
-            alpha,
-            beta,
-            <MNT6_753 as Pairing>::ScalarField::from(1),
-            <MNT6_753 as Pairing>::ScalarField::from(1),
-        )?;
-
-        assert_eq!(psi_polynomials.len(), w.len());
+        assert_eq!(trusted_setup.psi_polynomials.len(), w.len());
 
-        let a = alpha_1 + trusted_setup.evaluate_u(&w).context("Evaluating u")?;
-        let b = beta_2 + trusted_setup.evaluate_v(&w).context("Evaluating v")?;
-        let c = zip(&psi_polynomials, &w)
+        let a = trusted_setup.alpha + trusted_setup.evaluate_u(&w);
+        let b = trusted_setup.beta_2 + trusted_setup.evaluate_v(&w);
+        let c = zip(&trusted_setup.psi_polynomials, &w)
             .skip(qap.public_witness.len())
             .map(|(psi, a_i)| *psi * a_i)
             .reduce(std::ops::Add::add)
@@ -717,7 +3930,7 @@ mod tests {
             + ht_tau;
 
         let lhs = MNT6_753::pairing(a, b).0;
-        let rhs = (MNT6_753::pairing(alpha_1, beta_2)
+        let rhs = (MNT6_753::pairing(trusted_setup.alpha, trusted_setup.beta_2)
             + MNT6_753::pairing(c, <MNT6_753 as Pairing>::G2::generator()))
         .0;
 
@@ -739,12 +3952,23 @@ mod tests {
         assert_eq!(qap.degree(), 3);
 
         debug!("QAP derived");
+        let mut rng = rand::rng();
+        let alpha = rand_scalar(&mut rng);
+        let beta = rand_scalar(&mut rng);
+        let tau = rand_scalar(&mut rng);
+        let delta = rand_scalar(&mut rng);
         let trusted_setup: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
-            TrustedSetupOutput::new(qap.clone())?;
+            TrustedSetupOutput::new_with_secrets(
+                qap.clone(),
+                alpha,
+                beta,
+                tau,
+                Field::from(1),
+                delta,
+            )?;
 
         debug!("Trusted Setup complete");
 
-        let mut rng = rand::rng();
         let x = Field::from(rng.random_range(0..1000));
         let y = Field::from(rng.random_range(0..1000));
         let z = Field::from(rng.random_range(0..1000));
@@ -759,53 +3983,183 @@ mod tests {
             qap.max_polynomial_degree()
         );
 
-        let delta = rand_scalar(&mut rng);
-        let zero_polynomial_srs: Vec<<MNT6_753 as Pairing>::G1> =
-            TrustedSetupOutput::<MNT6_753>::zero_polynomial_srs(
-                qap.degree(),
-                (qap.degree()) - 1,
-                delta,
-                &trusted_setup.group_1_srs,
-            )?;
-
-        assert_eq!(zero_polynomial_srs.len(), qap.degree() - 1);
+        assert_eq!(trusted_setup.zero_polynomial_srs.len(), qap.degree() - 1);
 
-        let ht = trusted_setup.calculate_zero_polynomial(&w)?;
+        let ht = trusted_setup.quotient_polynomial(&w)?;
         debug!("zero polynomial: {:?}", ht);
         let ht_tau = ht
-            .evaluate_over_srs(&zero_polynomial_srs)
+            .evaluate_over_srs(&trusted_setup.zero_polynomial_srs)
             .context("Evaluating ht_tau")?;
 
-        let alpha = rand_scalar(&mut rng);
-        let beta = rand_scalar(&mut rng);
-        let alpha_1 = <MNT6_753 as Pairing>::G1::generator() * alpha;
-        let beta_2 = <MNT6_753 as Pairing>::G2::generator() * beta;
-        let psi_polynomials = TrustedSetupOutput::<MNT6_753>::psi_polynomials(
-            &qap,
-            &trusted_setup.group_1_srs,
-            alpha,
-            beta,
-            <MNT6_753 as Pairing>::ScalarField::from(1),
-            delta,
-        )?;
-
-        assert_eq!(psi_polynomials.len(), w.len());
+        assert_eq!(trusted_setup.psi_polynomials.len(), w.len());
 
-        let a = alpha_1 + trusted_setup.evaluate_u(&w).context("Evaluating u")?;
-        let b = beta_2 + trusted_setup.evaluate_v(&w).context("Evaluating v")?;
-        let c = zip(&psi_polynomials, &w)
+        let a = trusted_setup.alpha + trusted_setup.evaluate_u(&w);
+        let b = trusted_setup.beta_2 + trusted_setup.evaluate_v(&w);
+        let c = zip(&trusted_setup.psi_polynomials, &w)
             .skip(qap.public_witness.len())
             .map(|(psi, a_i)| *psi * a_i)
             .reduce(std::ops::Add::add)
             .ok_or(report!("Empty witness"))?
             + ht_tau;
 
-        let delta_2 = <MNT6_753 as Pairing>::G2::generator() * delta;
         let lhs = MNT6_753::pairing(a, b).0;
-        let rhs = (MNT6_753::pairing(alpha_1, beta_2) + MNT6_753::pairing(c, delta_2)).0;
+        let rhs = (MNT6_753::pairing(trusted_setup.alpha, trusted_setup.beta_2)
+            + MNT6_753::pairing(c, trusted_setup.delta_2))
+        .0;
 
         assert_eq!(lhs, rhs);
 
         Ok(())
     }
+    #[test]
+    fn setup_with_fixed_secrets_is_deterministic() -> Result<(), Report> {
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let qap = QAP::from(r1cs);
+
+        let (alpha, beta, tau, gamma, delta) = (
+            Field::from(2),
+            Field::from(3),
+            Field::from(5),
+            Field::from(7),
+            Field::from(11),
+        );
+        let a: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new_with_secrets(qap.clone(), alpha, beta, tau, gamma, delta)?;
+        let b: TrustedSetupOutput<MNT6_753> =
+            TrustedSetupOutput::new_with_secrets(qap, alpha, beta, tau, gamma, delta)?;
+
+        assert_eq!(a.group_1_srs, b.group_1_srs);
+        assert_eq!(a.group_2_srs, b.group_2_srs);
+        assert_eq!(a.zero_polynomial_srs, b.zero_polynomial_srs);
+        assert_eq!(a.psi_polynomials, b.psi_polynomials);
+        assert_eq!(a, b);
+
+        assert_eq!(
+            a.group_1_srs[2],
+            <MNT6_753 as Pairing>::G1::generator() * Field::from(25)
+        );
+        Ok(())
+    }
+    #[test]
+    #[cfg(feature = "transcript")]
+    fn transcript_challenges_depend_on_order() -> Result<(), Report> {
+        use crate::groth16::Transcript;
+
+        let mut rng = rand::rng();
+        let g1 = <MNT6_753 as Pairing>::G1::generator() * rand_scalar::<_, Field>(&mut rng);
+        let g2 = <MNT6_753 as Pairing>::G2::generator() * rand_scalar::<_, Field>(&mut rng);
+        let scalar: Field = rand_scalar(&mut rng);
+
+        let mut a = Transcript::<MNT6_753>::new(b"test");
+        a.append_g1(&g1)?;
+        a.append_g2(&g2)?;
+        a.append_scalar(&scalar)?;
+
+        let mut b = Transcript::<MNT6_753>::new(b"test");
+        b.append_g1(&g1)?;
+        b.append_g2(&g2)?;
+        b.append_scalar(&scalar)?;
+
+        let mut reordered = Transcript::<MNT6_753>::new(b"test");
+        reordered.append_scalar(&scalar)?;
+        reordered.append_g1(&g1)?;
+        reordered.append_g2(&g2)?;
+
+        let mut other_domain = Transcript::<MNT6_753>::new(b"other");
+        other_domain.append_g1(&g1)?;
+        other_domain.append_g2(&g2)?;
+        other_domain.append_scalar(&scalar)?;
+
+        let challenge = a.challenge_scalar()?;
+        assert_eq!(challenge, b.challenge_scalar()?);
+        assert_ne!(challenge, reordered.challenge_scalar()?);
+        assert_ne!(challenge, other_domain.challenge_scalar()?);
+
+        // Squeezing again should give a fresh challenge
+        assert_ne!(challenge, a.challenge_scalar()?);
+
+        // Challenges span the whole 753 bit field rather than one 512 bit digest, failing with probability 2^-241
+        use ark_ff::BigInteger;
+        assert!(challenge.into_bigint().num_bits() > 512);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "solidity")]
+    fn snarkjs_json_round_trip() -> Result<(), Report> {
+        use ark_bn254::{Bn254, Fr};
+        use ark_ec::CurveGroup;
+
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Fr> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let trusted_setup: TrustedSetupOutput<Bn254> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+
+        let mut rng = rand::rng();
+        let x = Fr::from(rng.random_range(0..1000));
+        let y = Fr::from(rng.random_range(0..1000));
+        let z = Fr::from(rng.random_range(0..1000));
+        let u = Fr::from(rng.random_range(0..1000));
+        let w = vec![Fr::from(1), x * y * z * u, x, y, z, u, x * y, z * u];
+        let proof = trusted_setup.prove(&w)?;
+
+        let json = proof.to_snarkjs_json();
+        debug!("{}", json);
+        assert_eq!(json["protocol"], "groth16");
+        assert_eq!(json["pi_a"][2], "1");
+        assert_eq!(json["pi_b"][2], serde_json::json!(["1", "0"]));
+
+        // snarkjs keeps G2 coordinates as [c0, c1], the reverse of the EVM's order
+        let b = proof.b.into_affine();
+        assert_eq!(json["pi_b"][0][0], b.x.c0.to_string());
+        assert_eq!(json["pi_b"][0][1], b.x.c1.to_string());
+
+        let parsed = Proof::<Bn254>::from_snarkjs_json(&json)?;
+        assert_eq!(parsed, proof);
+        assert!(parsed.verify(&trusted_setup, &[])?);
+
+        // Swapping c0 and c1 gives a point off the curve
+        let mut swapped = json.clone();
+        swapped["pi_b"][0] = serde_json::json!([json["pi_b"][0][1], json["pi_b"][0][0]]);
+        let error = Proof::<Bn254>::from_snarkjs_json(&swapped).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&error),
+            Some(&Groth16Error::InvalidEncoding)
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "solidity")]
+    fn export_solidity_verifier_has_public_psi_constants() -> Result<(), Report> {
+        use crate::groth16::export_solidity_verifier;
+        use ark_bn254::{Bn254, Fr};
+
+        init();
+
+        let (l, r, o) = r1cs_matrices();
+        let r1cs: R1CS<Fr> = R1CS::new(l.clone(), r.clone(), o.clone(), vec![1, 24]);
+        let trusted_setup: TrustedSetupOutput<Bn254> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let verifier = export_solidity_verifier(&trusted_setup.verifying_key_affine());
+
+        debug!("{}", verifier);
+        let psi_constants = verifier
+            .lines()
+            .filter(|line| line.trim_start().starts_with("uint256 constant psi"))
+            .count();
+        assert_eq!(psi_constants, 2 * 2);
+        assert!(verifier.contains("uint[2] calldata _pubSignals"));
+        assert!(verifier.contains("function verifyProof("));
+
+        let r1cs: R1CS<Fr> = R1CS::new(l, r, o, Vec::<i32>::new());
+        let trusted_setup: TrustedSetupOutput<Bn254> = TrustedSetupOutput::new(QAP::from(r1cs))?;
+        let verifier = export_solidity_verifier(&trusted_setup.verifying_key_affine());
+        assert!(!verifier.contains("uint256 constant psi"));
+        assert!(!verifier.contains("_pubSignals"));
+        Ok(())
+    }
 }
@@ -0,0 +1,154 @@
+use crate::circuits::QAP;
+use crate::error::Groth16Error;
+use crate::groth16::{Proof, TrustedSetupOutput};
+use crate::helpers::from_bytes;
+use ark_ec::pairing::Pairing;
+use rootcause::{Report, report};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The curves a setup can be created over with `dyn_setup`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CurveId {
+    /// MNT6-753, parsed from `"MNT6-753"`
+    Mnt6_753,
+    /// BN254, parsed from `"BN254"`. Needs the optional `solidity` feature, which brings in `ark-bn254`.
+    #[cfg(feature = "solidity")]
+    Bn254,
+}
+
+impl Display for CurveId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveId::Mnt6_753 => write!(f, "MNT6-753"),
+            #[cfg(feature = "solidity")]
+            CurveId::Bn254 => write!(f, "BN254"),
+        }
+    }
+}
+
+impl FromStr for CurveId {
+    type Err = Report;
+
+    /// Parse a curve name as written by `Display`, ignoring case.
+    fn from_str(name: &str) -> Result<Self, Report> {
+        match name.to_ascii_uppercase().as_str() {
+            "MNT6-753" => Ok(CurveId::Mnt6_753),
+            #[cfg(feature = "solidity")]
+            "BN254" => Ok(CurveId::Bn254),
+            _ => Err(report!(Groth16Error::UnsupportedCurve {
+                name: name.to_string()
+            })
+            .into()),
+        }
+    }
+}
+
+/// A trusted setup over a curve chosen at runtime, created with `dyn_setup`.
+/// Everything crossing this interface is in the compressed canonical arkworks encoding for the setup's curve,
+/// with witnesses encoded as a `Vec` of scalars.
+pub trait ErasedSetup {
+    /// The curve the setup is over.
+    fn curve(&self) -> CurveId;
+
+    /// Prove for an encoded witness, as `TrustedSetupOutput::prove`, returning the encoded proof.
+    fn prove(&self, witness: &[u8]) -> Result<Vec<u8>, Report>;
+
+    /// Verify an encoded proof against an encoded public witness, as `Proof::verify`.
+    fn verify(&self, proof: &[u8], public_witness: &[u8]) -> Result<bool, Report>;
+}
+
+/// A `TrustedSetupOutput` along with the id of its curve.
+struct Erased<C: Pairing> {
+    curve: CurveId,
+    setup: TrustedSetupOutput<C>,
+}
+
+impl<C: Pairing> ErasedSetup for Erased<C> {
+    fn curve(&self) -> CurveId {
+        self.curve
+    }
+
+    fn prove(&self, witness: &[u8]) -> Result<Vec<u8>, Report> {
+        let witness: Vec<C::ScalarField> = from_bytes(witness, "Decoding witness")?;
+        Ok(self.setup.prove(&witness)?.to_bytes())
+    }
+
+    fn verify(&self, proof: &[u8], public_witness: &[u8]) -> Result<bool, Report> {
+        let proof: Proof<C> = from_bytes(proof, "Decoding proof")?;
+        let public_witness: Vec<C::ScalarField> =
+            from_bytes(public_witness, "Decoding public witness")?;
+        proof.verify(&self.setup, &public_witness)
+    }
+}
+
+fn erase<C: Pairing>(curve: CurveId, qap_bytes: &[u8]) -> Result<Box<dyn ErasedSetup>, Report> {
+    let qap: QAP<C::ScalarField> = from_bytes(qap_bytes, "Decoding QAP")?;
+    Ok(Box::new(Erased {
+        curve,
+        setup: TrustedSetupOutput::<C>::new(qap)?,
+    }))
+}
+
+/// Run a new trusted setup over a curve chosen at runtime, for a QAP over that curve's scalar field in the compressed
+/// canonical encoding.
+pub fn dyn_setup(curve: CurveId, qap_bytes: &[u8]) -> Result<Box<dyn ErasedSetup>, Report> {
+    match curve {
+        CurveId::Mnt6_753 => erase::<ark_mnt6_753::MNT6_753>(curve, qap_bytes),
+        #[cfg(feature = "solidity")]
+        CurveId::Bn254 => erase::<ark_bn254::Bn254>(curve, qap_bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::{QAP, R1CS};
+    use crate::error::Groth16Error;
+    use crate::groth16::erased::{CurveId, dyn_setup};
+    use ark_serialize::CanonicalSerialize;
+    use rootcause::Report;
+
+    type Field = ark_mnt6_753::Fr;
+
+    fn encode<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, Report> {
+        let mut bytes = vec![];
+        value.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn erased_setup_by_curve_id() -> Result<(), Report> {
+        let l = vec![vec![0], vec![1], vec![0]];
+        let r = vec![vec![0], vec![0], vec![1]];
+        let o = vec![vec![1], vec![0], vec![0]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![6]);
+        let qap = QAP::from(r1cs);
+
+        let curve: CurveId = "mnt6-753".parse()?;
+        assert_eq!(curve, CurveId::Mnt6_753);
+        assert_eq!(curve.to_string(), "MNT6-753");
+        let setup = dyn_setup(curve, &encode(&qap)?)?;
+        assert_eq!(setup.curve(), CurveId::Mnt6_753);
+
+        let witness: Vec<Field> = [6, 2, 3].map(Field::from).to_vec();
+        let proof = setup.prove(&encode(&witness)?)?;
+        assert!(setup.verify(&proof, &encode(&vec![Field::from(6)])?)?);
+        assert!(!setup.verify(&proof, &encode(&vec![Field::from(7)])?)?);
+
+        let err = setup
+            .verify(&proof[1..], &encode(&vec![Field::from(6)])?)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::InvalidEncoding)
+        );
+        let err = "BLS12-381".parse::<CurveId>().unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::UnsupportedCurve {
+                name: "BLS12-381".to_string()
+            })
+        );
+        Ok(())
+    }
+}
@@ -1,46 +1,170 @@
+use crate::error::Groth16Error;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
 use ark_ff::Field;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::rand::SeedableRng;
+use ark_std::rand::rngs::StdRng;
 use rand::Rng;
+use rootcause::{Report, report};
 
+/// Sample a uniformly random scalar using arkworks' `UniformRand`.
 pub(crate) fn rand_scalar<T, S>(rng: &mut T) -> S
 where
     T: Rng,
     S: Field,
 {
-    let mut bytes = [0; 256];
-    let mut out = None;
-    while out.is_none() {
-        rng.fill_bytes(&mut bytes);
-        out = S::from_random_bytes(&bytes);
+    // arkworks is built against rand 0.8, so seed one of its RNGs from ours rather than
+    // trying to pass a rand 0.9 RNG through.
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+    S::rand(&mut StdRng::from_seed(seed))
+}
+
+/// Compare two byte sequences without branching on their contents, returning `1` if equal and `0` otherwise.
+/// Every byte is always visited, so the time taken only depends on the lengths, which aren't secret.
+#[cfg(feature = "std")]
+pub(crate) fn ct_eq<'a, A, B>(a: A, b: B) -> u8
+where
+    A: IntoIterator<Item = &'a u8>,
+    B: IntoIterator<Item = &'a u8>,
+{
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    let mut diff = 0u8;
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a_i), Some(b_i)) => diff |= a_i ^ b_i,
+            (None, None) => break,
+            _ => diff |= 1,
+        }
+    }
+    // 0 -> 1, anything else -> 0, without a data dependent branch
+    (((core::hint::black_box(diff) as u16).wrapping_sub(1) >> 8) & 1) as u8
+}
+
+/// The result of a constant time verification, from `Proof::verify_ct`.
+/// `Proof::verify` returns a plain `bool` from an `==` on the pairing outputs, so callers in timing sensitive contexts
+/// should prefer this and only convert to a `bool` with `into_bool` at the point they actually need to branch.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct VerifyResult(u8);
+
+#[cfg(feature = "std")]
+impl VerifyResult {
+    pub(crate) fn from_ct_eq(equal: u8) -> Self {
+        VerifyResult(equal)
     }
 
-    out.unwrap()
+    /// Whether the proof was accepted.
+    pub fn into_bool(self) -> bool {
+        core::hint::black_box(self.0) == 1
+    }
+}
+
+/// Serialize with arkworks' compressed canonical encoding, without serde's overhead of writing each byte as a
+/// separate element.
+pub(crate) fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![];
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a Vec can't fail");
+    bytes
+}
+
+/// Inverse of `to_bytes`, checking the decoded value is valid, e.g. that points are in the prime order subgroup.
+/// Fails with `Groth16Error::InvalidEncoding` under `context`.
+pub(crate) fn from_bytes<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    context: &'static str,
+) -> Result<T, Report> {
+    T::deserialize_compressed(bytes).map_err(|e| {
+        report!(Groth16Error::InvalidEncoding)
+            .attach(format!("{e}"))
+            .context(context)
+            .into()
+    })
+}
+
+/// Serde adapter writing an arkworks type as bytes in its compressed canonical encoding, for use with
+/// `#[serde(serialize_with = "ark_se")]`. Read back with `ark_de`, or `ark_de_no_validate` for trusted data.
+pub fn ark_se<S, A: CanonicalSerialize>(a: &A, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    ark_se_with_mode(a, s, Compress::Yes)
+}
+
+/// As `ark_se`, but uncompressed, which is about twice the size for curve points but skips recovering `y` from `x`
+/// when read back with `ark_de_uncompressed`.
+pub fn ark_se_uncompressed<S, A: CanonicalSerialize>(a: &A, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    ark_se_with_mode(a, s, Compress::No)
+}
+
+/// Serde adapter reading an arkworks type written by `ark_se`, checking points are on the curve and in the prime
+/// order subgroup.
+pub fn ark_de<'de, D, A: CanonicalDeserialize>(data: D) -> Result<A, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    ark_de_with_mode(data, Compress::Yes, Validate::Yes)
+}
+
+/// As `ark_de`, for data written by `ark_se_uncompressed`.
+pub fn ark_de_uncompressed<'de, D, A: CanonicalDeserialize>(data: D) -> Result<A, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    ark_de_with_mode(data, Compress::No, Validate::Yes)
 }
 
-pub(crate) fn ark_se<S, A: CanonicalSerialize>(a: &A, s: S) -> Result<S::Ok, S::Error>
+/// As `ark_de`, but skipping the subgroup checks, which dominate the cost of reading large SRS vectors.
+/// Only for data from a trusted source, such as a round trip through your own storage, as an invalid point breaks
+/// the security of anything built on it.
+pub fn ark_de_no_validate<'de, D, A: CanonicalDeserialize>(data: D) -> Result<A, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    ark_de_with_mode(data, Compress::Yes, Validate::No)
+}
+
+fn ark_se_with_mode<S, A: CanonicalSerialize>(
+    a: &A,
+    s: S,
+    compress: Compress,
+) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
     // ark_se and ark_de taken from https://github.com/arkworks-rs/algebra/issues/178#issuecomment-1413219278
     let mut bytes = vec![];
-    a.serialize_with_mode(&mut bytes, Compress::Yes)
+    a.serialize_with_mode(&mut bytes, compress)
         .map_err(serde::ser::Error::custom)?;
     s.serialize_bytes(&bytes)
 }
 
-pub(crate) fn ark_de<'de, D, A: CanonicalDeserialize>(data: D) -> Result<A, D::Error>
+fn ark_de_with_mode<'de, D, A: CanonicalDeserialize>(
+    data: D,
+    compress: Compress,
+    validate: Validate,
+) -> Result<A, D::Error>
 where
     D: serde::de::Deserializer<'de>,
 {
     let s: Vec<u8> = serde::de::Deserialize::deserialize(data)?;
-    let a = A::deserialize_with_mode(s.as_slice(), Compress::Yes, Validate::Yes);
+    let a = A::deserialize_with_mode(s.as_slice(), compress, validate);
     a.map_err(serde::de::Error::custom)
 }
 
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::circuits::{QAP, R1CS};
     use crate::groth16::TrustedSetupOutput;
-    use crate::helpers::rand_scalar;
+    use crate::helpers::{VerifyResult, ct_eq, rand_scalar};
     use crate::polynomial::Polynomial;
     use ark_ec::PrimeGroup;
     use ark_ec::pairing::{MillerLoopOutput, Pairing, PairingOutput};
@@ -94,6 +218,51 @@ mod tests {
         (l, r, o)
     }
 
+    #[test]
+    fn serde_adapter_modes_round_trip() -> Result<(), Report> {
+        use crate::helpers::{
+            ark_de, ark_de_no_validate, ark_de_uncompressed, ark_se, ark_se_uncompressed,
+        };
+        use serde::{Deserialize, Serialize};
+
+        type G1 = <MNT6_753 as Pairing>::G1;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Points {
+            #[serde(serialize_with = "ark_se", deserialize_with = "ark_de")]
+            compressed: Vec<G1>,
+            #[serde(
+                serialize_with = "ark_se_uncompressed",
+                deserialize_with = "ark_de_uncompressed"
+            )]
+            uncompressed: Vec<G1>,
+            #[serde(serialize_with = "ark_se", deserialize_with = "ark_de_no_validate")]
+            unvalidated: Vec<G1>,
+        }
+
+        let mut rng = rand::rng();
+        let points: Vec<G1> = (0..4)
+            .map(|_| G1::generator() * rand_scalar::<_, Field>(&mut rng))
+            .collect();
+        let value = Points {
+            compressed: points.clone(),
+            uncompressed: points.clone(),
+            unvalidated: points,
+        };
+        let json = serde_json::to_value(&value)?;
+        assert_eq!(serde_json::from_value::<Points>(json.clone())?, value);
+
+        let len = |field: &str| json[field].as_array().map_or(0, |bytes| bytes.len());
+        debug!(
+            "Compressed {} bytes, uncompressed {} bytes",
+            len("compressed"),
+            len("uncompressed")
+        );
+        assert!(len("uncompressed") > len("compressed"));
+        assert_eq!(len("unvalidated"), len("compressed"));
+        Ok(())
+    }
+
     #[test]
     fn serialisation_matches() -> Result<(), Report> {
         init();
@@ -127,15 +296,69 @@ mod tests {
         let proof = trusted_setup.prove(&w)?;
 
         debug!("Proof generated");
-        assert!(proof.verify(&trusted_setup, &qap.public_witness));
+        assert!(proof.verify(&trusted_setup, &qap.public_witness)?);
 
         let trusted_setup_serialized = serde_json::to_string(&trusted_setup)?;
         let trusted_setup_deserialized = serde_json::from_str(&trusted_setup_serialized)?;
         assert_eq!(trusted_setup, trusted_setup_deserialized);
 
+        // Independently decoded copies compare equal field by field, and a fresh setup for the same QAP doesn't
+        let from_json: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            serde_json::from_str(&trusted_setup_serialized)?;
+        let from_bytes = TrustedSetupOutput::from_bytes(&trusted_setup.to_bytes())?;
+        assert_eq!(from_json, from_bytes);
+        let other: TrustedSetupOutput<ark_mnt6_753::MNT6_753> =
+            TrustedSetupOutput::new(qap.clone())?;
+        assert_ne!(from_json, other);
+
         let proof_serialized = serde_json::to_string(&proof)?;
         let proof_deserialized = serde_json::from_str(&proof_serialized)?;
         assert_eq!(proof, proof_deserialized);
         Ok(())
     }
+
+    #[test]
+    fn rand_scalar_spans_field() {
+        let mut rng = rand::rng();
+        let scalars: Vec<Field> = (0..4096).map(|_| rand_scalar(&mut rng)).collect();
+        assert!(scalars.iter().all(|x| *x != Field::default()));
+
+        // With a 753 bit field, every one of these landing in the low byte would be astronomically unlikely
+        assert!(
+            scalars
+                .iter()
+                .any(|x| x.into_bigint() > Field::from(u8::MAX).into_bigint())
+        );
+        assert!(
+            scalars
+                .iter()
+                .any(|x| x.into_bigint() > Field::from(u64::MAX).into_bigint())
+        );
+        assert_eq!(
+            scalars
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            scalars.len()
+        );
+    }
+
+    #[test]
+    fn ct_eq_visits_every_byte() {
+        let a = [1u8, 2, 3, 4];
+        assert_eq!(ct_eq(&a, &[1, 2, 3, 4]), 1);
+        assert_eq!(ct_eq(&a, &[0, 2, 3, 4]), 0);
+        assert_eq!(ct_eq(&a, &[1, 2, 3, 5]), 0);
+        assert_eq!(ct_eq(&a, &[1, 2, 3]), 0);
+        assert_eq!(ct_eq(&[], &[]), 1);
+
+        // A mismatch on the first byte must not stop the comparison early
+        let visited = std::cell::Cell::new(0);
+        let counted = a.iter().inspect(|_| visited.set(visited.get() + 1));
+        assert_eq!(ct_eq(counted, &[9, 9, 9, 9]), 0);
+        assert_eq!(visited.get(), a.len());
+
+        assert!(VerifyResult::from_ct_eq(ct_eq(&a, &a)).into_bool());
+        assert!(!VerifyResult::from_ct_eq(ct_eq(&a, &[0; 4])).into_bool());
+    }
 }
@@ -14,6 +14,8 @@
 //!Additionally, errors are handled using the `rootcause` crate which allows for developer friendly backtraces including
 //!additional context and attached debug information (think showing operands to a failed call). All Results are annotated
 //!with at the minimum a `.context()` call describing the operation that failed.
+//!The root cause of every report is an `error::Groth16Error`, which can be recovered with `Groth16Error::find` to
+//!programmatically branch on the kind of failure.
 //!
 //!The core types are `circuits::R1CS`, `circuits::QAP`, `groth16::TrustedSetupOutput`, `groth16::Proof`.
 //!
@@ -23,18 +25,32 @@
 //!- Use `QAP::from` to convert R1CS to QAP
 //!- Generate a Trusted Setup using `TrustedSetupOutput::new`
 //!- Generate a proof using `trusted_setup.prove(witness)`
-//!- Verify proof with `proof.verify()`
+//!- Verify proof with `proof.verify()`, which returns an error if verification couldn't be carried out
 //!
 //!The library is extensively tested with 100% test coverage, which can be verified with
 //!`cargo install tarpaulin; cargo tarpaulin --engine llvm` or by looking at the coverage report in `coverage`. Note that
 //!although the coverage report reports 414/418 lines covered, the missing 4 lines are all match arms that are incorrectly
 //!marked as being code, which can be verified by going into `polynomial.rs` in the report and finding the red lines.
 //!
+//!Without the default `std` feature the crate is `no_std` and only needs `alloc`, keeping the polynomial and circuit
+//!arithmetic in `polynomial` and `circuits`, and the error type in `error`. The `groth16` module (which needs the
+//!thread RNG, IO, and `HashMap`), `circuits::export`, `QAP::verify_rounds` (though not `QAP::verify_rounds_with_rng`),
+//!and backtraces on reports are `std`-only.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 /// Contains the types for Rank 1 Constraint Systems and Quadratic Arithmetic Programs.
 pub mod circuits;
+/// Contains the error type at the root of every returned `Report`.
+pub mod error;
 /// Contains types for the actual Groth16 proof algorithm.
+#[cfg(feature = "std")]
 pub mod groth16;
-mod helpers;
+/// Contains serde adapters for arkworks types, and the result of constant time verification.
+pub mod helpers;
 /// Contains types for polynomials.
 pub mod polynomial;
+/// Contains `wasm-bindgen` bindings to prove and verify from JavaScript over serialized inputs.
+#[cfg(feature = "wasm")]
+pub mod wasm;
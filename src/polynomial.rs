@@ -1,15 +1,67 @@
+use crate::error::Groth16Error;
+use crate::helpers::rand_scalar;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
 use ark_ec::CurveGroup;
-use ark_ff::Field;
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::iterable::Iterable;
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::iter::{Sum, zip};
+use core::ops::{Add, AddAssign, Div, Index, Mul, Neg, Sub, SubAssign};
 use log::trace;
+use rand::Rng;
 use rootcause::prelude::ResultExt;
 use rootcause::{Report, bail, report};
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::iter::{Sum, zip};
-use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign};
+
+mod multipoint;
+
+/// An SRS with a table of small odd multiples of each element, for `Polynomial::evaluate_over_prepared_srs`.
+/// Trades memory for speed, holding `2^(window - 2)` points per SRS element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedSrs<T: CurveGroup> {
+    window: usize,
+    /// `odd_multiples[i][j]` is `(2j + 1) * srs[i]`
+    odd_multiples: Vec<Vec<T::Affine>>,
+}
+
+impl<T: CurveGroup> PreparedSrs<T> {
+    /// Precompute the tables for `srs` with a window of `window` bits, clamped to between 2 and 12.
+    pub fn new(srs: &[T], window: usize) -> Self {
+        let window = window.clamp(2, 12);
+        let table_size = 1 << (window - 2);
+        let multiples: Vec<T> = srs
+            .iter()
+            .flat_map(|base| {
+                let double = base.double();
+                core::iter::successors(Some(*base), move |x| Some(*x + double)).take(table_size)
+            })
+            .collect();
+
+        PreparedSrs {
+            window,
+            odd_multiples: T::normalize_batch(&multiples)
+                .chunks(table_size)
+                .map(<[_]>::to_vec)
+                .collect(),
+        }
+    }
+
+    /// The number of SRS elements, i.e. one more than the highest degree polynomial it can evaluate.
+    pub fn len(&self) -> usize {
+        self.odd_multiples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.odd_multiples.is_empty()
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+}
 
 /// Represents a polynomial over a finite field. Supports scalar and polynomial addition, subtraction, multiplication, division.
 /// Can be generated via Lagrangian interpolation over a vector.
@@ -26,30 +78,168 @@ where
 
 impl<F: Field> Polynomial<F> {
     /// Evaluate the polynomial over an SRS to get the `y` value without knowing `x`.
-    pub fn evaluate_over_srs<T>(&self, srs: &Vec<T>) -> Result<T, Report>
+    /// The zero polynomial evaluates to the identity.
+    ///
+    /// Uses a windowed bucket method rather than one scalar multiplication per coefficient. Each coefficient is split
+    /// into signed `c` bit window digits between `-2^(c-1)` and `2^(c-1)`, and each non-zero digit adds its SRS element
+    /// to, or subtracts it from, one of `2^(c-1)` buckets. The buckets are combined with a running sum, and the
+    /// windows are joined with `c` doublings each.
+    pub fn evaluate_over_srs<T>(&self, srs: &[T]) -> Result<T, Report>
     where
-        T: MulAssign<F> + CurveGroup + Debug,
+        F: PrimeField,
+        T: CurveGroup,
     {
-        if srs.len() < self.coefficients.len() {
-            return Err(report!("SRS too small for polynomial")
-                .attach(format!(
-                    "SRS degree: {:?} (supports polynomial of degree {:?})",
-                    srs.len(),
-                    srs.len().checked_sub(1)
-                ))
-                .attach(format!("Polynomial degree: {:?}", self.degree())));
+        self.evaluate_over_srs_with_window(srs, None)
+    }
+
+    /// Evaluate the polynomial over an SRS as in `evaluate_over_srs`, with a window size of `window` bits.
+    /// If `window` is `None` it's picked from the number of coefficients. Windows are clamped to between 2 and 16 bits.
+    pub fn evaluate_over_srs_with_window<T>(
+        &self,
+        srs: &[T],
+        window: Option<usize>,
+    ) -> Result<T, Report>
+    where
+        F: PrimeField,
+        T: CurveGroup,
+    {
+        self.bucket_evaluation(srs, window)
+    }
+
+    /// Evaluate the polynomial over an SRS in affine form as in `evaluate_over_srs_with_window`.
+    /// Adding an affine point to a projective one is cheaper than adding two projective points, so this is faster
+    /// when the same SRS is evaluated over many times.
+    pub fn evaluate_over_affine_srs<T>(
+        &self,
+        srs: &[T::Affine],
+        window: Option<usize>,
+    ) -> Result<T, Report>
+    where
+        F: PrimeField,
+        T: CurveGroup,
+    {
+        self.bucket_evaluation(srs, window)
+    }
+
+    /// Evaluate the polynomial over an SRS prepared with `PreparedSrs::new`, giving the same result as
+    /// `evaluate_over_srs`. Worth it when evaluating many polynomials over the same SRS, e.g. repeated proving.
+    ///
+    /// Each coefficient is written in width-`w` non-adjacent form, and the terms are evaluated together with one
+    /// shared doubling per bit, adding or subtracting the precomputed multiple of an SRS element at each non-zero
+    /// digit. Non-zero digits are at least `w` bits apart, so this does about one addition per `w + 1` bits of each
+    /// coefficient.
+    pub fn evaluate_over_prepared_srs<T>(&self, srs: &PreparedSrs<T>) -> Result<T, Report>
+    where
+        F: PrimeField,
+        T: CurveGroup,
+    {
+        self.check_srs_length(srs.len())?;
+
+        let digits: Vec<Vec<i64>> = self
+            .coefficients
+            .iter()
+            .map(|x| {
+                x.into_bigint()
+                    .find_wnaf(srs.window)
+                    .expect("PreparedSrs window is always between 2 and 12")
+            })
+            .collect();
+        let num_digits = digits.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut result = T::default();
+        for position in (0..num_digits).rev() {
+            result.double_in_place();
+            for (digits, multiples) in zip(&digits, &srs.odd_multiples) {
+                match digits.get(position) {
+                    Some(&digit) if digit > 0 => result += multiples[(digit / 2) as usize],
+                    Some(&digit) if digit < 0 => result -= multiples[(-digit / 2) as usize],
+                    _ => {}
+                }
+            }
         }
 
-        self.coefficients
+        Ok(result)
+    }
+
+    fn check_srs_length(&self, have: usize) -> Result<(), Report> {
+        if have < self.coefficients.len() {
+            return Err(report!(Groth16Error::SrsTooSmall {
+                needed: self.coefficients.len(),
+                have,
+            })
+            .attach(format!(
+                "SRS degree: {:?} (supports polynomial of degree {:?})",
+                have,
+                have.checked_sub(1)
+            ))
+            .attach(format!("Polynomial degree: {:?}", self.degree()))
+            .into());
+        }
+        Ok(())
+    }
+
+    fn bucket_evaluation<T, B>(&self, srs: &[B], window: Option<usize>) -> Result<T, Report>
+    where
+        F: PrimeField,
+        T: CurveGroup + AddAssign<B> + SubAssign<B>,
+        B: Copy,
+    {
+        self.check_srs_length(srs.len())?;
+
+        let scalars: Vec<F::BigInt> = self.coefficients.iter().map(|x| x.into_bigint()).collect();
+        // Roughly log2 of the number of terms, which balances bucket additions against the number of windows
+        let window = window
+            .unwrap_or((usize::BITS - scalars.len().leading_zeros()) as usize)
+            .clamp(2, 16);
+        let num_bits = F::MODULUS_BIT_SIZE as usize;
+        // One extra window for the carry out of the top one
+        let num_windows = num_bits.div_ceil(window) + 1;
+        let half = 1i64 << (window - 1);
+
+        // A window above half becomes negative by carrying one into the next window up
+        let digits: Vec<Vec<i64>> = scalars
             .iter()
-            .enumerate()
-            .map(|(degree, coefficient)| {
-                let mut result = srs[degree];
-                result *= *coefficient;
-                result
+            .map(|scalar| {
+                let mut carry = 0;
+                (0..num_windows)
+                    .map(|index| {
+                        let window_start = index * window;
+                        let unsigned = (0..window)
+                            .filter(|bit| {
+                                window_start + bit < num_bits && scalar.get_bit(window_start + bit)
+                            })
+                            .fold(carry, |digit, bit| digit + (1 << bit));
+                        carry = i64::from(unsigned > half);
+                        unsigned - (carry << window)
+                    })
+                    .collect()
             })
-            .reduce(Add::add)
-            .ok_or(report!("Polynomial has no coefficients"))
+            .collect();
+
+        let mut result = T::default();
+        for index in (0..num_windows).rev() {
+            for _ in 0..window {
+                result.double_in_place();
+            }
+
+            let mut buckets = vec![T::default(); half as usize];
+            for (digits, base) in zip(&digits, srs) {
+                match digits[index] {
+                    0 => {}
+                    digit if digit > 0 => buckets[digit as usize - 1] += *base,
+                    digit => buckets[(-digit) as usize - 1] -= *base,
+                }
+            }
+
+            // sum_j j * buckets[j - 1], as a running sum from the top bucket down
+            let mut running_sum = T::default();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += bucket;
+                result += running_sum;
+            }
+        }
+
+        Ok(result)
     }
 
     /// Evaluate polynomial at some point `x`.
@@ -65,6 +255,72 @@ impl<F: Field> Polynomial<F> {
         self.coefficients.len().checked_sub(1).unwrap_or(0)
     }
 
+    /// Number of coefficients up to and including the last non-zero one.
+    fn trimmed_len(&self) -> usize {
+        self.coefficients
+            .iter()
+            .rposition(|x| *x != F::default())
+            .map_or(0, |pos| pos + 1)
+    }
+
+    /// Drop any trailing zero coefficients, so `degree()` is the true degree and equal polynomials compare equal.
+    /// The zero polynomial is left with no coefficients.
+    pub fn trim(&mut self) {
+        let len = self.trimmed_len();
+        self.coefficients.truncate(len);
+    }
+
+    /// A copy of the polynomial without any trailing zero coefficients, as `trim`.
+    pub fn trimmed(&self) -> Self {
+        Polynomial {
+            coefficients: self.coefficients[..self.trimmed_len()].to_vec(),
+        }
+    }
+
+    /// Coefficients of `self * rhs`, sized from the true degrees of each side so trailing zeroes don't inflate it.
+    fn product_coefficients(&self, rhs: &Polynomial<F>) -> Vec<F> {
+        let a = &self.coefficients[..self.trimmed_len()];
+        let b = &rhs.coefficients[..rhs.trimmed_len()];
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+
+        // n^2 approach from https://home.cse.ust.hk/~dekai/271/notes/L03/L03.pdf page 4.
+        let mut out = vec![F::default(); a.len() + b.len() - 1];
+        for (i, a_i) in a.iter().enumerate() {
+            for (j, b_j) in b.iter().enumerate() {
+                out[i + j] += *a_i * *b_j;
+            }
+        }
+        out
+    }
+
+    /// Number of stored coefficients, including any trailing zeroes.
+    pub fn len(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Whether there are no stored coefficients. Note a polynomial can be zero without being empty, see `is_zero`.
+    pub fn is_empty(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The coefficients in ascending degree order x^0, x^1, etc, including any trailing zeroes.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// Iterate over the coefficients in ascending degree order.
+    pub fn iter(&self) -> core::slice::Iter<'_, F> {
+        self.coefficients.iter()
+    }
+
+    /// The coefficient of `x^i`, or `None` if `i` is past the stored coefficients. Mathematically such coefficients
+    /// are zero, but unlike indexing this lets the caller tell them apart from a stored zero.
+    pub fn get(&self, i: usize) -> Option<&F> {
+        self.coefficients.get(i)
+    }
+
     pub fn is_zero(&self) -> bool {
         self.coefficients.len() == 0 || self.coefficients.iter().all(|x| *x == F::default())
     }
@@ -94,11 +350,236 @@ impl<F: Field> Polynomial<F> {
             .all(|x| *x == F::default())
     }
 
+    /// Multiply by x^k by prepending `k` zero coefficients, which avoids a full polynomial multiplication.
+    pub fn shift(&self, k: usize) -> Polynomial<F> {
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        Polynomial {
+            coefficients: [vec![F::default(); k], self.coefficients.clone()].concat(),
+        }
+    }
+
+    /// The reciprocal polynomial, with the coefficients in reverse order, so `a_0 + a_1 x + ... + a_n x^n` becomes
+    /// `a_n + a_(n-1) x + ... + a_0 x^n`, i.e. `x^n * self(1/x)` for `n = degree()`.
+    /// Any stored trailing zeroes become leading zeroes, and any zero low coefficients become trailing zeroes, so
+    /// `trim` first if `n` should be the true degree.
+    pub fn reverse(&self) -> Polynomial<F> {
+        Polynomial {
+            coefficients: self.coefficients.iter().rev().copied().collect(),
+        }
+    }
+
+    /// The terms of degree below `k`, i.e. `self mod x^k`.
+    pub fn truncate(&self, k: usize) -> Polynomial<F> {
+        Polynomial {
+            coefficients: self.coefficients[..k.min(self.coefficients.len())].to_vec(),
+        }
+    }
+
+    /// Split into the terms of degree below `k` and the rest divided by `x^k`, so that `low + high.shift(k) == self`.
+    /// If `k` is at least the number of coefficients, `high` is empty.
+    pub fn split_at(&self, k: usize) -> (Polynomial<F>, Polynomial<F>) {
+        let (low, high) = self.coefficients.split_at(k.min(self.coefficients.len()));
+        (
+            Polynomial::new(low.to_vec()),
+            Polynomial::new(high.to_vec()),
+        )
+    }
+
+    /// Compose with another polynomial, giving `self(inner(x))`.
+    /// Uses Horner's method, so a constant `self` composes to that same constant.
+    pub fn compose(&self, inner: &Polynomial<F>) -> Polynomial<F> {
+        let composed = self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(Polynomial::new(vec![]), |acc, coefficient| {
+                &(&acc * inner) + &Polynomial::new(vec![*coefficient])
+            });
+
+        Polynomial::from_coefficients(composed.coefficients)
+    }
+
+    /// Substitute `x -> c * x`, giving `self(c * x)`, by multiplying each coefficient `a_i` by `c^i`.
+    /// This is the same as `compose` with `c * x` but takes `O(n)` rather than a multiplication per coefficient, e.g.
+    /// to move a polynomial between domains that differ by a scale factor.
+    pub fn scale_domain(&self, c: F) -> Polynomial<F> {
+        let mut power = F::ONE;
+        self.coefficients
+            .iter()
+            .map(|coefficient| {
+                let scaled = *coefficient * power;
+                power *= c;
+                scaled
+            })
+            .collect()
+    }
+
+    /// Long division, returning the quotient and remainder rather than failing if the remainder is non-zero.
+    pub fn div_rem(&self, rhs: &Polynomial<F>) -> Result<(Polynomial<F>, Polynomial<F>), Report> {
+        if rhs.is_zero() {
+            bail!(Groth16Error::ZeroDivisor)
+        }
+
+        trace!("Dividing {:?}/{:?}", self, rhs);
+        // Trailing zeros would give the divisor a zero leading coefficient to divide by
+        let rhs = rhs.trimmed();
+        let mut quotient = Polynomial::new(vec![]);
+        let mut remainder = self.clone();
+
+        // Every iteration pops a coefficient off the remainder, so this terminates even if the leading coefficient
+        // was already zero
+        while !remainder.is_zero() && remainder.degree() >= rhs.degree() {
+            let tmp = (remainder.lead().clone() / rhs.lead())
+                .context("Dividing lead")
+                .attach(format!("LHS: {:?}", remainder.lead()))
+                .attach(format!("RHS: {:?}", rhs.lead()))?;
+            quotient += tmp.clone();
+            // tmp is a monomial, so multiplying by it is just a shift and scale
+            let coefficient = tmp.coefficients.last().copied().unwrap_or(F::default());
+            remainder -= &rhs.shift(tmp.degree()) * coefficient;
+            // The leading term is now cancelled, so drop it
+            remainder.coefficients.pop();
+            trace!("Q: {:?}\nR: {:?}\nTmp: {:?}", quotient, remainder, tmp);
+        }
+
+        Ok((quotient, remainder))
+    }
+
+    /// Divide by the vanishing polynomial of `roots`, i.e. `(x - r_1)(x - r_2)...(x - r_n)`, by synthetic division
+    /// one root at a time. This takes `O(n)` per root rather than the `O(n^2)` of general long division.
+    /// If `roots` are the powers `1, w, ..., w^(n-1)` of a root of unity of order `n`, for `n` a power of two as
+    /// from `QAP::roots_of_unity_domain`, the divisor is `x^n - 1` and this takes `O(n)` overall.
+    /// Fails with `Groth16Error::NonZeroRemainder` if any of `roots` isn't a root of what's left to divide.
+    pub fn divide_by_vanishing(&self, roots: &[F]) -> Result<Polynomial<F>, Report> {
+        if is_roots_of_unity(roots) {
+            return self.divide_by_x_n_minus_one(roots.len());
+        }
+
+        let mut quotient = self.trimmed();
+        for (i, root) in roots.iter().enumerate() {
+            if quotient.is_zero() {
+                break;
+            }
+            // Horner's rule from the top coefficient down, the last value carried being the remainder
+            let mut carry = F::ZERO;
+            for coefficient in quotient.coefficients.iter_mut().rev() {
+                carry = *coefficient + carry * root;
+                *coefficient = carry;
+            }
+            let remainder = quotient.coefficients.remove(0);
+            if remainder != F::ZERO {
+                return Err(report!(Groth16Error::NonZeroRemainder)
+                    .attach(format!("Root {i} is not a root: {:?}", root))
+                    .attach(format!("Remainder: {:?}", remainder))
+                    .into());
+            }
+        }
+        Ok(quotient)
+    }
+
+    /// Divide by `x^n - 1`. The coefficient of `x^i` in `q(x)(x^n - 1)` is `q_(i-n) - q_i`, so the quotient can be
+    /// read off from the top down.
+    fn divide_by_x_n_minus_one(&self, n: usize) -> Result<Polynomial<F>, Report> {
+        let dividend = self.trimmed();
+        let len = dividend.coefficients.len().saturating_sub(n);
+        let mut quotient = vec![F::ZERO; len];
+        for j in (0..len).rev() {
+            quotient[j] =
+                dividend.coefficients[j + n] + quotient.get(j + n).copied().unwrap_or(F::ZERO);
+        }
+        for (i, coefficient) in dividend.coefficients.iter().take(n).enumerate() {
+            let remainder = *coefficient + quotient.get(i).copied().unwrap_or(F::ZERO);
+            if remainder != F::ZERO {
+                return Err(report!(Groth16Error::NonZeroRemainder)
+                    .attach(format!("Dividing by x^{n} - 1"))
+                    .attach(format!("Remainder coefficient of x^{i}: {:?}", remainder))
+                    .into());
+            }
+        }
+        Ok(Polynomial::new(quotient))
+    }
+
+    /// The linear combination `sum(coeffs[i] * polys[i])`, scaling and adding each coefficient in place rather than
+    /// building a scaled copy of every polynomial.
+    /// Fails with `Groth16Error::WitnessLength` if there isn't one coefficient per polynomial.
+    pub fn linear_combination(
+        polys: &[Polynomial<F>],
+        coeffs: &[F],
+    ) -> Result<Polynomial<F>, Report> {
+        if polys.len() != coeffs.len() {
+            bail!(Groth16Error::WitnessLength {
+                expected: polys.len(),
+                got: coeffs.len()
+            });
+        }
+
+        let len = polys
+            .iter()
+            .map(|p| p.coefficients.len())
+            .max()
+            .unwrap_or(0);
+        let mut coefficients = vec![F::default(); len];
+        for (p, c) in zip(polys, coeffs) {
+            for (acc, x) in zip(&mut coefficients, &p.coefficients) {
+                *acc += *x * c;
+            }
+        }
+        Ok(Polynomial { coefficients })
+    }
+
+    /// The vanishing polynomial (x - p_1)(x - p_2)...(x - p_n), which is zero at exactly the given points.
+    /// For no points this is the constant 1.
+    pub fn vanishing(points: &[F]) -> Self {
+        points
+            .iter()
+            .map(|x| Polynomial::new(vec![-*x, F::from(1)]))
+            .reduce(Mul::mul)
+            .unwrap_or(Polynomial::from(vec![1]))
+    }
+
     /// Create from a vector of coefficients in ascending degree order x^0, x^1, etc
     pub fn new(vec: Vec<F>) -> Self {
         Polynomial { coefficients: vec }
     }
 
+    /// Create from a vector of field element coefficients in ascending degree order x^0, x^1, etc,
+    /// trimming any trailing zero coefficients.
+    /// Unlike `Polynomial::from`, this never needs a type annotation when you already have a `Vec<F>`.
+    pub fn from_coefficients(vec: Vec<F>) -> Self {
+        let mut polynomial = Polynomial { coefficients: vec };
+        polynomial.trim();
+        polynomial
+    }
+
+    /// Create a polynomial of exactly degree `degree` with uniformly random coefficients, resampling the leading
+    /// coefficient until it's non-zero. Useful for tests and benchmarks.
+    pub fn random<R: Rng>(degree: usize, rng: &mut R) -> Self {
+        Self::sample(degree, || rand_scalar(rng))
+    }
+
+    /// As `random`, but sampling with `ark_ff::UniformRand` from an arkworks RNG such as `ark_std::test_rng()`,
+    /// rather than from a `rand` RNG.
+    pub fn rand<R: ark_std::rand::Rng + ?Sized>(degree: usize, rng: &mut R) -> Self {
+        Self::sample(degree, || F::rand(rng))
+    }
+
+    /// A polynomial of exactly degree `degree` with coefficients drawn from `sample`, redrawing the leading
+    /// coefficient until it's non-zero.
+    fn sample(degree: usize, mut sample: impl FnMut() -> F) -> Self {
+        let mut coefficients: Vec<F> = (0..degree).map(|_| sample()).collect();
+        let lead = loop {
+            let lead = sample();
+            if lead != F::default() {
+                break lead;
+            }
+        };
+        coefficients.push(lead);
+        Polynomial { coefficients }
+    }
+
     /// Find a polynomial by doing Lagrange interpolation over a vector,
     /// where the `x` coordinate is taken to be the index of the element in the vector plus one.
     /// # Examples
@@ -106,31 +587,31 @@ impl<F: Field> Polynomial<F> {
     /// // Interpolates the set of points [(1,3), (2,10), (3,11)]
     /// interpolate_from_vector(vec![3, 10, 11])
     /// ```
-    pub fn interpolate_from_vector(vec: &Vec<F>) -> Self {
-        vec.iter()
-            .enumerate()
+    pub fn interpolate_from_vector(vec: &[F]) -> Self {
+        let xs: Vec<F> = (1..vec.len() + 1).map(|x| F::from(x as u128)).collect();
+        Self::interpolate(&xs, vec)
+    }
+
+    /// Find a polynomial by doing Lagrange interpolation over the points `(xs[i], ys[i])`.
+    /// The `x` coordinates must be distinct.
+    pub fn interpolate(xs: &[F], ys: &[F]) -> Self {
+        zip(xs, ys)
             .map(|(x, y)| {
-                let x = F::from((x + 1) as u128);
-                &(&(1..vec.len() + 1)
+                &(&xs
+                    .iter()
                     .filter_map(|x_i| {
-                        if F::from(x_i as u128) == x {
+                        if x_i == x {
                             None
                         } else {
                             Some(Polynomial {
-                                coefficients: vec![-F::from(x_i as u128), F::from(1)],
+                                coefficients: vec![-*x_i, F::from(1)],
                             })
                         }
                     })
-                    .reduce(std::ops::Mul::mul)
+                    .reduce(core::ops::Mul::mul)
                     .unwrap_or(Polynomial::from(vec![1]))
-                    / (1..vec.len() + 1)
-                        .filter_map(|x_i| {
-                            if F::from(x_i as u128) == x {
-                                None
-                            } else {
-                                Some(x - F::from(x_i as u128))
-                            }
-                        })
+                    / xs.iter()
+                        .filter_map(|x_i| if x_i == x { None } else { Some(*x - x_i) })
                         .reduce(Mul::mul)
                         .unwrap_or(F::from(1)))
                     * *y
@@ -139,18 +620,58 @@ impl<F: Field> Polynomial<F> {
     }
 }
 
+/// Whether `roots` are `1, w, w^2, ..., w^(n-1)` for `w` a root of unity of order exactly `n`, a power of two, in
+/// which case their vanishing polynomial is `x^n - 1`. The order of `w` divides `n` as `w^n = 1`, so for `n` a power
+/// of two it's exactly `n` unless `w^(n/2) = 1`.
+fn is_roots_of_unity<F: Field>(roots: &[F]) -> bool {
+    let n = roots.len();
+    if !n.is_power_of_two() || roots[0] != F::ONE {
+        return false;
+    }
+    let w = roots.get(1).copied().unwrap_or(F::ONE);
+    roots.windows(2).all(|pair| pair[1] == pair[0] * w)
+        && roots[n - 1] * w == F::ONE
+        && (n == 1 || roots[n / 2] != F::ONE)
+}
+
 impl<T, F: Field> From<Vec<T>> for Polynomial<F>
 where
     T: Copy,
     F: From<T>,
 {
     fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().map(F::from).collect()
+    }
+}
+
+impl<T, F: Field> From<&[T]> for Polynomial<F>
+where
+    T: Copy,
+    F: From<T>,
+{
+    fn from(slice: &[T]) -> Self {
+        slice.iter().map(|x| F::from(*x)).collect()
+    }
+}
+
+impl<F: Field> FromIterator<F> for Polynomial<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
         Polynomial {
-            coefficients: vec.iter().map(|x| F::from(*x)).collect(),
+            coefficients: iter.into_iter().collect(),
         }
     }
 }
 
+/// `poly[i]` is the coefficient of `x^i`. Like `Vec`, this panics if `i` is past the stored coefficients, use
+/// `Polynomial::get` to check.
+impl<F: Field> Index<usize> for Polynomial<F> {
+    type Output = F;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.coefficients[i]
+    }
+}
+
 impl<F: Field> Add for Polynomial<F> {
     type Output = Polynomial<F>;
 
@@ -195,6 +716,14 @@ impl<F: Field> AddAssign for Polynomial<F> {
     }
 }
 
+impl<F: Field> Sub for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
 impl<F: Field> Sub for &Polynomial<F> {
     type Output = Polynomial<F>;
 
@@ -202,16 +731,16 @@ impl<F: Field> Sub for &Polynomial<F> {
         let mut a = self.clone();
         let mut b = (*rhs).clone();
 
-        match a.degree().cmp(&b.degree()) {
+        match a.coefficients.len().cmp(&b.coefficients.len()) {
             Ordering::Less => {
                 // Pad a
-                let new_elems = b.degree() - a.degree();
+                let new_elems = b.coefficients.len() - a.coefficients.len();
                 let padding = vec![F::default(); new_elems];
                 a.coefficients = [a.coefficients, padding].concat()
             }
             Ordering::Greater => {
                 // Pad b
-                let new_elems = a.degree() - b.degree();
+                let new_elems = a.coefficients.len() - b.coefficients.len();
                 let padding = vec![F::default(); new_elems];
                 b.coefficients = [b.coefficients, padding].concat();
             }
@@ -225,6 +754,22 @@ impl<F: Field> Sub for &Polynomial<F> {
     }
 }
 
+impl<F: Field> Neg for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl<F: Field> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Self::Output {
+        self.coefficients.iter().map(|x| -*x).collect()
+    }
+}
+
 impl<F: Field> SubAssign for Polynomial<F> {
     fn sub_assign(&mut self, rhs: Self) {
         *self = &*self - &rhs;
@@ -235,42 +780,9 @@ impl<F: Field> Mul for &Polynomial<F> {
     type Output = Polynomial<F>;
 
     fn mul(self, rhs: &Polynomial<F>) -> Self::Output {
-        let mut a = self.clone();
-        let mut b = rhs.clone();
-
-        match a.degree().cmp(&b.degree()) {
-            Ordering::Less => {
-                // Pad a
-                let new_elems = b.degree() - a.degree();
-                let padding = vec![F::default(); new_elems];
-                a.coefficients = [a.coefficients, padding].concat()
-            }
-            Ordering::Greater => {
-                // Pad b
-                let new_elems = a.degree() - b.degree();
-                let padding = vec![F::default(); new_elems];
-                b.coefficients = [b.coefficients, padding].concat();
-            }
-            _ => {}
-        }
-
-        // n^2 approach from https://home.cse.ust.hk/~dekai/271/notes/L03/L03.pdf page 4.
-        let mut out = vec![F::default(); 1 + a.degree() + b.degree()]; // +1 for constant
-        for k in 0..out.len() {
-            let mut coefficient = F::default();
-            for i in 0..k + 1 {
-                coefficient += *a.coefficients.get(i).unwrap_or(&F::default())
-                    * *b.coefficients.get(k - i).unwrap_or(&F::default())
-            }
-            out[k] = coefficient
-        }
-
-        // Truncate trailing zeroes
-        if let Some(pos) = out.iter().rposition(|x| *x != F::default()) {
-            out.truncate(pos + 1)
-        } else {
-            out = Vec::new()
-        }
+        let out = self.product_coefficients(rhs);
+        // The product of the non-zero leading coefficients is non-zero, so there's nothing to truncate
+        debug_assert!(out.last().is_none_or(|x| *x != F::default()));
         Polynomial { coefficients: out }
     }
 }
@@ -288,12 +800,28 @@ impl<F: Field> Div for Polynomial<F> {
 
     fn div(self, rhs: Self) -> Self::Output {
         if rhs.is_zero() {
-            bail!("Divisor is zero")
+            bail!(Groth16Error::ZeroDivisor)
         } else if self.is_zero() {
             return Ok(Polynomial::new(vec![]));
         }
 
         if self.is_lead() && rhs.is_lead() {
+            if self.degree() < rhs.degree() {
+                return Err(report!(Groth16Error::NonZeroRemainder)
+                    .attach(format!("Dividend: {:?}", self))
+                    .attach(format!("Divisor: {:?}", rhs))
+                    .attach(format!(
+                        "Partial quotient: {:?}",
+                        Polynomial::<F>::new(vec![])
+                    ))
+                    .attach(format!("Remainder: {:?}", self))
+                    .attach(format!(
+                        "Stalled at degree {}, below the divisor's degree {}",
+                        self.degree(),
+                        rhs.degree()
+                    ))
+                    .into());
+            }
             let degree = self.degree() - rhs.degree();
 
             let mut coefficients = vec![F::default(); degree + 1];
@@ -309,18 +837,19 @@ impl<F: Field> Div for Polynomial<F> {
             return Ok(out);
         }
 
-        trace!("Dividing {:?}/{:?}", self, rhs);
-        let mut quotient = Polynomial::new(vec![]);
-        let mut remainder = self.clone();
-
-        while !remainder.is_zero() && remainder.degree() >= rhs.degree() {
-            let tmp = (remainder.lead().clone() / rhs.lead())
-                .context("Dividing lead")
-                .attach(format!("LHS: {:?}", self.lead()))
-                .attach(format!("RHS: {:?}", rhs.lead()))?;
-            quotient += tmp.clone();
-            remainder -= &tmp * &rhs;
-            trace!("Q: {:?}\nR: {:?}\nTmp: {:?}", quotient, remainder, tmp);
+        let (quotient, remainder) = self.div_rem(&rhs)?;
+        if !remainder.is_zero() {
+            return Err(report!(Groth16Error::NonZeroRemainder)
+                .attach(format!("Dividend: {:?}", self))
+                .attach(format!("Divisor: {:?}", rhs))
+                .attach(format!("Partial quotient: {:?}", quotient))
+                .attach(format!("Remainder: {:?}", remainder))
+                .attach(format!(
+                    "Stalled at degree {}, below the divisor's degree {}",
+                    remainder.trimmed().degree(),
+                    rhs.trimmed().degree()
+                ))
+                .into());
         }
 
         Ok(quotient)
@@ -348,19 +877,38 @@ impl<F: Field> Div<F> for &Polynomial<F> {
 
 impl<F: Field> Sum for Polynomial<F> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        iter.reduce(std::ops::Add::add).unwrap_or(Polynomial {
+        iter.reduce(core::ops::Add::add).unwrap_or(Polynomial {
             coefficients: Vec::new(),
         })
     }
 }
 
+impl<'a, F: Field> Sum<&'a Polynomial<F>> for Polynomial<F> {
+    fn sum<I: Iterator<Item = &'a Polynomial<F>>>(iter: I) -> Self {
+        iter.fold(Polynomial::new(Vec::new()), |acc, x| &acc + x)
+    }
+}
+
+/// Collecting polynomials sums them, so `polys.iter().collect()` is the same as `polys.iter().sum()`
+impl<F: Field> FromIterator<Polynomial<F>> for Polynomial<F> {
+    fn from_iter<I: IntoIterator<Item = Polynomial<F>>>(iter: I) -> Self {
+        iter.into_iter().sum()
+    }
+}
+
+impl<'a, F: Field> FromIterator<&'a Polynomial<F>> for Polynomial<F> {
+    fn from_iter<I: IntoIterator<Item = &'a Polynomial<F>>>(iter: I) -> Self {
+        iter.into_iter().sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_ec::pairing::Pairing;
     use ark_mnt6_753::MNT6_753;
     use log::debug;
-    use rand::{Rng, RngCore};
+    use rand::Rng;
 
     type Field = ark_mnt6_753::Fr;
 
@@ -412,6 +960,271 @@ mod tests {
         assert_eq!(&a - &b, c);
     }
 
+    #[test]
+    fn polynomial_sub_pads_by_length() {
+        init();
+        // The zero polynomial has no coefficients but the same degree as a constant, which used to truncate the result
+        let a = Polynomial::<Field>::new(vec![]);
+        let b = Polynomial::from(vec![1, 2]);
+        assert_eq!(&a - &b, Polynomial::from(vec![-1, -2]));
+        assert_eq!(b.clone() - a, b);
+
+        // Trailing zeros count towards the length
+        let a = Polynomial {
+            coefficients: vec![Field::from(3), Field::from(0), Field::from(0)],
+        };
+        let b = Polynomial::from(vec![1, 2]);
+        let c = Polynomial {
+            coefficients: vec![Field::from(2), Field::from(-2), Field::from(0)],
+        };
+        assert_eq!(a.clone() - b.clone(), c);
+        assert_eq!((&b - &a).trimmed(), Polynomial::from(vec![-2, 2]));
+    }
+
+    #[test]
+    fn polynomial_from_coefficients() {
+        let coefficients = vec![
+            Field::from(3),
+            Field::from(5),
+            Field::from(0),
+            Field::from(0),
+        ];
+        let poly = Polynomial::from_coefficients(coefficients);
+        assert_eq!(poly, Polynomial::from(vec![3, 5]));
+        assert_eq!(poly.degree(), 1);
+
+        let zero = Polynomial::from_coefficients(vec![Field::from(0)]);
+        assert!(zero.is_zero());
+        assert_eq!(zero, Polynomial::new(vec![]));
+    }
+
+    #[test]
+    fn polynomial_reverse() {
+        use ark_ff::Field as _;
+
+        let poly: Polynomial<Field> = Polynomial::from(vec![0, 1, 2, 3, 0]);
+        assert_eq!(poly.reverse(), Polynomial::from(vec![0, 3, 2, 1, 0]));
+        assert_eq!(poly.trimmed().reverse(), Polynomial::from(vec![3, 2, 1, 0]));
+        assert_eq!(poly.reverse().reverse(), poly);
+        assert_eq!(
+            poly.trimmed().reverse().reverse().trimmed(),
+            Polynomial::from(vec![0, 1, 2, 3])
+        );
+
+        let x = Field::from(7);
+        let poly: Polynomial<Field> = Polynomial::random(6, &mut rand::rng());
+        assert_eq!(poly.reverse().reverse(), poly);
+        assert_eq!(
+            poly.reverse().evaluate(&x),
+            x.pow([6]) * poly.evaluate(&x.inverse().unwrap())
+        );
+    }
+
+    #[test]
+    fn polynomial_index() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![3, 5, 0, 7]);
+        assert_eq!(poly[0], Field::from(3));
+        assert_eq!(poly[2], Field::from(0));
+        assert_eq!(poly[3], Field::from(7));
+        assert_eq!(poly.get(1), Some(&Field::from(5)));
+        assert_eq!(poly.get(4), None);
+        assert_eq!(Polynomial::<Field>::new(vec![]).get(0), None);
+    }
+
+    #[test]
+    fn polynomial_trim() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![1, 2, 0, 0]);
+        assert_eq!(poly.degree(), 3);
+        assert_eq!(poly.trimmed().degree(), 1);
+        assert_eq!(poly.trimmed(), Polynomial::from(vec![1, 2]));
+
+        let mut sum = &poly + &Polynomial::from(vec![0, 0, 0, 0, 0]);
+        assert_ne!(sum, Polynomial::from(vec![1, 2]));
+        sum.trim();
+        assert_eq!(sum, Polynomial::from(vec![1, 2]));
+
+        let mut zero: Polynomial<Field> = Polynomial::from(vec![0, 0]);
+        zero.trim();
+        assert!(zero.is_empty());
+    }
+
+    #[test]
+    fn polynomial_coefficients() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![1, 2, 3]);
+        assert_eq!(
+            poly.coefficients(),
+            &[Field::from(1), Field::from(2), Field::from(3)]
+        );
+        assert_eq!(poly.len(), 3);
+        assert!(!poly.is_empty());
+        assert_eq!(
+            poly.iter().copied().collect::<Vec<_>>(),
+            poly.coefficients()
+        );
+
+        let zero: Polynomial<Field> = Polynomial::from(vec![0]);
+        assert_eq!(zero.len(), 1);
+        assert!(zero.is_zero());
+        assert!(!zero.is_empty());
+        assert!(Polynomial::<Field>::new(vec![]).is_empty());
+    }
+
+    #[test]
+    fn polynomial_from_slice_and_iterator() {
+        let coefficients = [1, 0, 5, 7];
+        let from_vec: Polynomial<Field> = Polynomial::from(coefficients.to_vec());
+        let from_slice: Polynomial<Field> = Polynomial::from(&coefficients[..]);
+        let collected: Polynomial<Field> = coefficients.iter().copied().map(Field::from).collect();
+        assert_eq!(from_slice, from_vec);
+        assert_eq!(collected, from_vec);
+        assert_eq!(
+            from_vec.iter().copied().collect::<Polynomial<Field>>(),
+            from_vec
+        );
+        assert!(
+            std::iter::empty::<Field>()
+                .collect::<Polynomial<Field>>()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn polynomial_shift() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![1, 2]);
+        assert_eq!(poly.shift(2), Polynomial::from(vec![0, 0, 1, 2]));
+        assert_eq!(poly.shift(0), poly);
+        assert_eq!(poly.shift(3), &poly * &Polynomial::from(vec![0, 0, 0, 1]));
+
+        let zero = Polynomial::<Field>::new(vec![]);
+        assert!(zero.shift(4).is_zero());
+        assert_eq!(zero.shift(4), zero);
+        let zero: Polynomial<Field> = Polynomial::from(vec![0, 0]);
+        assert!(zero.shift(4).is_zero());
+
+        let poly: Polynomial<Field> = Polynomial::from(vec![3, 0, 7, 1]);
+        for x in [0, 1, 5, 1000].map(Field::from) {
+            assert_eq!(poly.shift(2).evaluate(&x), x * x * poly.evaluate(&x));
+        }
+    }
+
+    #[test]
+    fn polynomial_split_at() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![1, 2, 0, 4, 5]);
+        for k in 0..8 {
+            let (low, high) = poly.split_at(k);
+            assert_eq!(&low + &high.shift(k), poly);
+            assert_eq!(low, poly.truncate(k));
+            assert!(low.len() <= k);
+        }
+
+        let (low, high) = poly.split_at(2);
+        assert_eq!(low, Polynomial::from(vec![1, 2]));
+        assert_eq!(high, Polynomial::from(vec![0, 4, 5]));
+
+        let (low, high) = poly.split_at(10);
+        assert_eq!(low, poly);
+        assert!(high.is_empty());
+    }
+
+    #[test]
+    fn polynomial_compose() {
+        let outer: Polynomial<Field> = Polynomial::from(vec![1, 0, 1]);
+        let inner: Polynomial<Field> = Polynomial::from(vec![1, 1]);
+        assert_eq!(outer.compose(&inner), Polynomial::from(vec![2, 2, 1]));
+
+        let x = Field::from(7);
+        assert_eq!(
+            outer.compose(&inner).evaluate(&x),
+            outer.evaluate(&inner.evaluate(&x))
+        );
+
+        let constant: Polynomial<Field> = Polynomial::from(vec![5]);
+        assert_eq!(constant.compose(&inner), constant);
+
+        let zero = Polynomial::<Field>::new(vec![]);
+        assert!(zero.compose(&inner).is_zero());
+        assert_eq!(outer.compose(&zero), Polynomial::from(vec![1]));
+    }
+
+    #[test]
+    fn polynomial_scale_domain() {
+        let mut rng = rand::rng();
+        let poly: Polynomial<Field> = Polynomial::random(20, &mut rng);
+        let c: Field = crate::helpers::rand_scalar(&mut rng);
+        let x: Field = crate::helpers::rand_scalar(&mut rng);
+        let scaled = poly.scale_domain(c);
+        assert_eq!(scaled.evaluate(&x), poly.evaluate(&(c * x)));
+        assert_eq!(
+            scaled,
+            poly.compose(&Polynomial::new(vec![Field::from(0), c]))
+        );
+
+        assert_eq!(poly.scale_domain(Field::from(1)), poly);
+        assert_eq!(
+            poly.scale_domain(Field::from(0)).trimmed(),
+            Polynomial::new(vec![poly[0]]).trimmed()
+        );
+    }
+
+    #[test]
+    fn divide_by_vanishing_matches_div() -> Result<(), Report> {
+        let mut rng = rand::rng();
+        let roots: Vec<Field> = (1..=5).map(Field::from).collect();
+        let vanishing = Polynomial::vanishing(&roots);
+        let multiple = &Polynomial::<Field>::random(6, &mut rng) * &vanishing;
+
+        let fast = multiple.divide_by_vanishing(&roots)?;
+        let slow = (multiple.clone() / vanishing)?;
+        assert_eq!(fast.trimmed(), slow.trimmed());
+        assert_eq!(&fast * &Polynomial::vanishing(&roots), multiple.trimmed());
+
+        let err = (&multiple + &Polynomial::from(vec![1]))
+            .divide_by_vanishing(&roots)
+            .unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn divide_by_vanishing_over_roots_of_unity() -> Result<(), Report> {
+        use ark_ff::{FftField, Field as _};
+
+        let mut rng = rand::rng();
+        for n in [1u64, 2, 8] {
+            let w = Field::get_root_of_unity(n).unwrap();
+            let roots: Vec<Field> = (0..n).map(|i| w.pow([i])).collect();
+            let vanishing = Polynomial::vanishing(&roots);
+            let multiple = &Polynomial::<Field>::random(5, &mut rng) * &vanishing;
+
+            let fast = multiple.divide_by_vanishing(&roots)?;
+            let slow = (multiple.clone() / vanishing)?;
+            assert_eq!(fast.trimmed(), slow.trimmed());
+
+            let err = (&multiple + &Polynomial::from(vec![0, 1]))
+                .divide_by_vanishing(&roots)
+                .unwrap_err();
+            assert_eq!(
+                Groth16Error::find(&err),
+                Some(&Groth16Error::NonZeroRemainder)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn polynomial_neg() {
+        let poly: Polynomial<Field> = Polynomial::from(vec![-2, 3]);
+        assert_eq!(-&poly, Polynomial::from(vec![2, -3]));
+        assert_eq!(-(-poly.clone()), poly);
+        assert_eq!(&poly + &(-&poly), Polynomial::from(vec![0, 0]));
+
+        let zero = Polynomial::<Field>::new(vec![]);
+        assert_eq!(-&zero, zero);
+    }
+
     #[test]
     fn polynomial_mult() {
         let a = Polynomial {
@@ -436,17 +1249,29 @@ mod tests {
         assert_eq!(a * b, c);
     }
 
+    #[test]
+    fn polynomial_mult_different_degrees() {
+        // x^10 + 1, with trailing zeroes that shouldn't count towards the degree
+        let a: Polynomial<Field> = Polynomial::from(vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        let b: Polynomial<Field> = Polynomial::from(vec![2, 3, 0]);
+
+        assert_eq!(a.product_coefficients(&b).len(), 10 + 1 + 1);
+        assert_eq!(
+            &a * &b,
+            Polynomial::from(vec![2, 3, 0, 0, 0, 0, 0, 0, 0, 0, 2, 3])
+        );
+        assert_eq!(&b * &a, &a * &b);
+
+        let zero = Polynomial::<Field>::new(vec![]);
+        assert!(a.product_coefficients(&zero).is_empty());
+        assert_eq!(&a * &Polynomial::from(vec![0, 0]), zero);
+    }
+
     #[test]
     fn polynomial_interpolation() {
         let mut rng = rand::rng();
         let length: usize = rng.random_range(1..30);
-        let vec: Vec<Field> = (1..length + 1)
-            .map(|_| {
-                let mut bytes = [0u8; 32];
-                rng.fill_bytes(&mut bytes);
-                ark_ff::Field::from_random_bytes(&bytes).unwrap()
-            })
-            .collect();
+        let vec: Vec<Field> = Polynomial::random(length - 1, &mut rng).coefficients;
         assert_eq!(vec.len(), length);
         let poly = Polynomial::interpolate_from_vector(&vec);
         assert_eq!(poly.degree(), length - 1);
@@ -458,6 +1283,77 @@ mod tests {
         assert_eq!(vec, out);
     }
 
+    #[test]
+    fn linear_combination_matches_sum() -> Result<(), Report> {
+        let mut rng = rand::rng();
+        let polys: Vec<Polynomial<Field>> = [3, 0, 5, 5, 1]
+            .map(|degree| Polynomial::random(degree, &mut rng))
+            .to_vec();
+        let coeffs: Vec<Field> = (0..polys.len()).map(|_| rand_scalar(&mut rng)).collect();
+
+        let summed: Polynomial<Field> = zip(&polys, &coeffs).map(|(p, c)| p * *c).sum();
+        assert_eq!(Polynomial::linear_combination(&polys, &coeffs)?, summed);
+        assert_eq!(
+            Polynomial::<Field>::linear_combination(&[], &[])?,
+            Polynomial::new(vec![])
+        );
+
+        let err = Polynomial::linear_combination(&polys, &coeffs[1..]).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::WitnessLength {
+                expected: 5,
+                got: 4
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sum_by_reference() {
+        let mut rng = rand::rng();
+        let polys: Vec<Polynomial<Field>> = [2, 4, 0, 4]
+            .map(|degree| Polynomial::random(degree, &mut rng))
+            .to_vec();
+
+        let owned: Polynomial<Field> = polys.clone().into_iter().sum();
+        let borrowed: Polynomial<Field> = polys.iter().sum();
+        assert_eq!(borrowed, owned);
+        assert_eq!(polys.iter().collect::<Polynomial<Field>>(), owned);
+        assert_eq!(polys.into_iter().collect::<Polynomial<Field>>(), owned);
+        assert_eq!(
+            Vec::<Polynomial<Field>>::new()
+                .iter()
+                .sum::<Polynomial<Field>>(),
+            Polynomial::new(vec![])
+        );
+    }
+
+    #[test]
+    fn random_polynomial_has_exact_degree() {
+        let mut rng = rand::rng();
+        for degree in [0, 1, 7, 7, 7, 20] {
+            let poly: Polynomial<Field> = Polynomial::random(degree, &mut rng);
+            assert_eq!(poly.degree(), degree);
+            assert_eq!(poly.len(), degree + 1);
+            assert_ne!(poly.coefficients()[degree], Field::from(0));
+        }
+        assert_ne!(
+            Polynomial::<Field>::random(7, &mut rng),
+            Polynomial::random(7, &mut rng)
+        );
+    }
+
+    #[test]
+    fn rand_polynomial_has_exact_degree() {
+        let mut rng = ark_std::test_rng();
+        for degree in [0, 3, 7, 7, 7] {
+            let poly: Polynomial<Field> = Polynomial::rand(degree, &mut rng);
+            assert_eq!(poly.degree(), degree);
+            assert_ne!(poly.coefficients()[degree], Field::from(0));
+        }
+    }
+
     #[test]
     fn polynomial_evaluation() {
         let poly = Polynomial::<Field>::from(vec![3, 2, 4]);
@@ -474,8 +1370,186 @@ mod tests {
         assert_eq!((c / b)?, a);
 
         let b: Polynomial<Field> = Polynomial::from(vec![0, 0, 0]);
-        assert!((a / b).is_err());
+        let err = (a.clone() / b).unwrap_err();
+        assert_eq!(Groth16Error::find(&err), Some(&Groth16Error::ZeroDivisor));
+
+        // x^2 + 1 isn't divisible by x + 1
+        let a: Polynomial<Field> = Polynomial::from(vec![1, 0, 1]);
+        let b: Polynomial<Field> = Polynomial::from(vec![1, 1]);
+        let (quotient, remainder) = a.div_rem(&b)?;
+        assert_eq!(quotient, Polynomial::from(vec![-1, 1]));
+        assert_eq!(remainder, Polynomial::from(vec![2]));
+        let err = (a / b).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+
+        let a: Polynomial<Field> = Polynomial::from(vec![0, 1]);
+        let b: Polynomial<Field> = Polynomial::from(vec![0, 0, 1]);
+        let err = (a / b).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_divisor_error_locates_remainder() -> Result<(), Report> {
+        init();
+        // x^3 + 2x + 1 = (x + 1)(x^2 - x + 3) - 2
+        let a: Polynomial<Field> = Polynomial::from(vec![1, 2, 0, 1]);
+        let b: Polynomial<Field> = Polynomial::from(vec![1, 1]);
+        let err = (a.clone() / b.clone()).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::NonZeroRemainder)
+        );
+        let attachments: Vec<&String> = err
+            .iter_reports()
+            .flat_map(|report| report.attachments().iter())
+            .filter_map(|attachment| attachment.downcast_inner::<String>())
+            .collect();
+        for expected in [
+            format!("Dividend: {:?}", a),
+            format!("Divisor: {:?}", b),
+            format!(
+                "Partial quotient: {:?}",
+                Polynomial::<Field>::from(vec![3, -1, 1])
+            ),
+            format!("Remainder: {:?}", Polynomial::<Field>::from(vec![-2])),
+            "Stalled at degree 0, below the divisor's degree 1".to_string(),
+        ] {
+            assert!(attachments.contains(&&expected), "Missing {expected}");
+        }
+
+        // A divisor with trailing zeros still has a usable leading coefficient
+        let b: Polynomial<Field> =
+            Polynomial::new(vec![Field::from(1), Field::from(1), Field::from(0)]);
+        let (quotient, remainder) = a.div_rem(&b)?;
+        assert_eq!(quotient, Polynomial::from(vec![3, -1, 1]));
+        assert_eq!(remainder, Polynomial::from(vec![-2]));
+        Ok(())
+    }
+
+    #[test]
+    fn windowed_srs_evaluation_matches_naive() -> Result<(), Report> {
+        use ark_ec::PrimeGroup;
+        use ark_ff::Field as _;
+        use ark_std::UniformRand;
+
+        type G1 = <MNT6_753 as Pairing>::G1;
+        let mut rng = ark_std::test_rng();
+        let tau = Field::rand(&mut rng);
+        let srs: Vec<G1> = (0..21)
+            .map(|i| G1::generator() * tau.pow([i as u64]))
+            .collect();
+
+        let poly = Polynomial::rand(20, &mut rng);
+        let coefficients: Vec<Field> = poly.coefficients.clone();
+
+        let naive: G1 = zip(&srs, &coefficients).map(|(x, c)| *x * c).sum();
+        assert_eq!(poly.evaluate_over_srs(&srs)?, naive);
+        assert_eq!(
+            poly.evaluate_over_srs(&srs)?,
+            G1::generator() * poly.evaluate(&tau)
+        );
+
+        // Small and edge case coefficients
+        let poly: Polynomial<Field> = Polynomial::from(vec![0, 1, -1, 2, 0, 255, 256]);
+        let naive: G1 = zip(&srs, &poly.coefficients).map(|(x, c)| *x * c).sum();
+        assert_eq!(poly.evaluate_over_srs(&srs)?, naive);
+
+        assert_eq!(
+            Polynomial::<Field>::new(vec![]).evaluate_over_srs(&srs)?,
+            G1::default()
+        );
+
+        // Any window size, over either form of the SRS, gives the same result
+        let poly = Polynomial::new(coefficients);
+        let expected = G1::generator() * poly.evaluate(&tau);
+        let affine = G1::normalize_batch(&srs);
+        for window in [1, 2, 3, 7, 12] {
+            assert_eq!(
+                poly.evaluate_over_srs_with_window(&srs, Some(window))?,
+                expected
+            );
+            assert_eq!(
+                poly.evaluate_over_affine_srs::<G1>(&affine, Some(window))?,
+                expected
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prepared_srs_evaluation_matches_plain() -> Result<(), Report> {
+        use ark_ec::PrimeGroup;
+        use ark_ff::Field as _;
+        use ark_std::UniformRand;
+        use std::time::Instant;
+
+        type G1 = <MNT6_753 as Pairing>::G1;
+        let mut rng = ark_std::test_rng();
+        let tau = Field::rand(&mut rng);
+        let srs: Vec<G1> = (0..16)
+            .map(|i| G1::generator() * tau.pow([i as u64]))
+            .collect();
+        let polys: Vec<Polynomial<Field>> =
+            (0..8).map(|_| Polynomial::rand(15, &mut rng)).collect();
 
+        for window in [2, 3, 5] {
+            let prepared = PreparedSrs::new(&srs, window);
+            assert_eq!(prepared.len(), srs.len());
+            for poly in &polys {
+                assert_eq!(
+                    poly.evaluate_over_prepared_srs(&prepared)?,
+                    poly.evaluate_over_srs(&srs)?
+                );
+            }
+        }
+
+        // Small and edge case coefficients
+        let prepared = PreparedSrs::new(&srs, 4);
+        let poly: Polynomial<Field> = Polynomial::from(vec![0, 1, -1, 2, 0, 255, 256, -7]);
+        assert_eq!(
+            poly.evaluate_over_prepared_srs(&prepared)?,
+            poly.evaluate_over_srs(&srs)?
+        );
+        assert_eq!(
+            Polynomial::<Field>::new(vec![]).evaluate_over_prepared_srs(&prepared)?,
+            G1::default()
+        );
+        let too_long = Polynomial::new(vec![Field::from(1); 17]);
+        let err = too_long.evaluate_over_prepared_srs(&prepared).unwrap_err();
+        assert_eq!(
+            Groth16Error::find(&err),
+            Some(&Groth16Error::SrsTooSmall {
+                needed: 17,
+                have: 16
+            })
+        );
+
+        let start = Instant::now();
+        let plain_results = polys
+            .iter()
+            .map(|poly| poly.evaluate_over_srs(&srs))
+            .collect::<Result<Vec<G1>, Report>>()?;
+        let plain = start.elapsed();
+        let start = Instant::now();
+        let prepared_results = polys
+            .iter()
+            .map(|poly| poly.evaluate_over_prepared_srs(&prepared))
+            .collect::<Result<Vec<G1>, Report>>()?;
+        assert_eq!(prepared_results, plain_results);
+        debug!(
+            "{} evaluations: plain {:?}, prepared {:?}",
+            polys.len(),
+            plain,
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -485,6 +1559,51 @@ mod tests {
         let poly = Polynomial::<Field>::from(vec![1, 2, 3, 4, 5]);
 
         let evaluation = poly.evaluate_over_srs(&srs);
-        assert!(evaluation.is_err())
+        assert_eq!(
+            Groth16Error::find(&evaluation.unwrap_err()),
+            Some(&Groth16Error::SrsTooSmall { needed: 5, have: 0 })
+        );
+    }
+
+    #[test]
+    fn vanishing_polynomial_roots() {
+        let points = [1, 2, 3].map(Field::from);
+        let t = Polynomial::vanishing(&points);
+        assert_eq!(t.degree(), 3);
+        assert!(points.iter().all(|x| t.evaluate(x) == Field::from(0)));
+        assert!(
+            [0, 4, 5, -1]
+                .map(Field::from)
+                .iter()
+                .all(|x| t.evaluate(x) != Field::from(0))
+        );
+        assert_eq!(
+            Polynomial::<Field>::vanishing(&[]),
+            Polynomial::from(vec![1])
+        );
+    }
+
+    /// Run with `cargo test --no-default-features` to check the arithmetic works with only `alloc`.
+    #[test]
+    fn arithmetic_without_std() -> Result<(), Report> {
+        use ark_ec::PrimeGroup;
+        use ark_ff::Field as _;
+
+        let xs = [1, 2, 3, 4].map(Field::from);
+        let ys = [5, 0, 7, 11].map(Field::from);
+        let poly = Polynomial::interpolate(&xs, &ys);
+        assert!(zip(&xs, &ys).all(|(x, y)| poly.evaluate(x) == *y));
+
+        let t = Polynomial::vanishing(&xs);
+        let product = &(&poly * &t) + &Polynomial::from(vec![3]);
+        let (quotient, remainder) = product.div_rem(&t)?;
+        assert_eq!(quotient, poly);
+        assert!((&remainder - &Polynomial::from(vec![3])).is_zero());
+
+        let g = <MNT6_753 as Pairing>::G1::generator();
+        let tau = Field::from(9);
+        let srs: Vec<_> = (0..4u64).map(|i| g * tau.pow([i])).collect();
+        assert_eq!(poly.evaluate_over_srs(&srs)?, g * poly.evaluate(&tau));
+        Ok(())
     }
 }
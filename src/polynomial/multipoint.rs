@@ -0,0 +1,204 @@
+use crate::polynomial::Polynomial;
+use alloc::vec;
+use alloc::vec::Vec;
+use ark_ff::FftField;
+
+/// Below this many points or coefficients `evaluate_batch` just evaluates each point in turn.
+const MULTIPOINT_THRESHOLD: usize = 32;
+/// Below this many coefficients on the shorter side, multiply by the schoolbook method rather than an FFT.
+const FFT_THRESHOLD: usize = 16;
+
+impl<F: FftField> Polynomial<F> {
+    /// Evaluate at each of `points`, returning the values in the same order.
+    ///
+    /// For many points on a large polynomial this uses multipoint evaluation: the points' vanishing polynomials are
+    /// multiplied up a subproduct tree, then the polynomial is reduced modulo each node on the way back down, leaving
+    /// its value at each point in the leaves. With FFT multiplication and division by Newton iteration this takes
+    /// `O(M(n) log n)` rather than `O(n * d)` for `n` points and degree `d`. If the field has no subgroup of the
+    /// required power of two size, products fall back to the schoolbook method.
+    pub fn evaluate_batch(&self, points: &[F]) -> Vec<F> {
+        let poly = self.trimmed();
+        if points.len() < MULTIPOINT_THRESHOLD || poly.len() < MULTIPOINT_THRESHOLD {
+            return points.iter().map(|x| poly.evaluate(x)).collect();
+        }
+
+        // tree[0] holds (x - p_i) for each point, each level above the products of pairs from the one below
+        let mut tree: Vec<Vec<Vec<F>>> = vec![points.iter().map(|p| vec![-*p, F::ONE]).collect()];
+        while let Some(level) = tree.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => mul(a, b),
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            tree.push(next);
+        }
+
+        let mut remainders = vec![poly.coefficients];
+        for level in tree.iter().rev() {
+            remainders = level
+                .iter()
+                .enumerate()
+                .map(|(i, node)| rem(&remainders[i / 2], node))
+                .collect();
+        }
+        remainders
+            .into_iter()
+            .map(|r| r.first().copied().unwrap_or_default())
+            .collect()
+    }
+}
+
+/// In place radix-2 FFT of `a`, whose length is a power of two, at the powers of `omega`, a root of unity of that order.
+fn fft<F: FftField>(a: &mut [F], omega: F) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let omega_len = omega.pow([(n / len) as u64]);
+        for chunk in a.chunks_mut(len) {
+            let (low, high) = chunk.split_at_mut(len / 2);
+            let mut w = F::ONE;
+            for (x, y) in low.iter_mut().zip(high) {
+                let t = *y * w;
+                *y = *x - t;
+                *x += t;
+                w *= omega_len;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Coefficients of the product, with exactly `a.len() + b.len() - 1` of them.
+fn mul<F: FftField>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let omega = F::get_root_of_unity(n as u64);
+    let (Some(omega), false) = (omega, a.len().min(b.len()) < FFT_THRESHOLD) else {
+        let mut out = vec![F::default(); len];
+        for (i, a_i) in a.iter().enumerate() {
+            for (j, b_j) in b.iter().enumerate() {
+                out[i + j] += *a_i * *b_j;
+            }
+        }
+        return out;
+    };
+
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.resize(n, F::default());
+    b.resize(n, F::default());
+    fft(&mut a, omega);
+    fft(&mut b, omega);
+    for (a_i, b_i) in a.iter_mut().zip(&b) {
+        *a_i *= b_i;
+    }
+    let omega_inverse = omega.inverse().expect("Roots of unity are non-zero");
+    fft(&mut a, omega_inverse);
+    let n_inverse = F::from(n as u64)
+        .inverse()
+        .expect("The FFT size divides the group order, so is non-zero in the field");
+    a.truncate(len);
+    a.iter_mut().for_each(|x| *x *= n_inverse);
+    a
+}
+
+/// The first `k` coefficients of the power series `1 / f`, by Newton iteration `g <- g * (2 - f * g)`, which doubles
+/// the number of correct coefficients each step. `f` must have a non-zero constant term.
+fn inverse_series<F: FftField>(f: &[F], k: usize) -> Vec<F> {
+    let mut g = vec![f[0].inverse().expect("Constant term is non-zero")];
+    while g.len() < k {
+        let len = (2 * g.len()).min(k);
+        let mut e = mul(&f[..f.len().min(len)], &g);
+        e.truncate(len);
+        e.iter_mut().for_each(|x| *x = -*x);
+        e[0] += F::from(2u64);
+        g = mul(&g, &e);
+        g.truncate(len);
+    }
+    g.truncate(k);
+    g
+}
+
+/// The remainder of `a` divided by the monic `b`, found by computing the quotient from the reversed polynomials as
+/// `rev(a) / rev(b)` to `deg(a) - deg(b) + 1` terms.
+fn rem<F: FftField>(a: &[F], b: &[F]) -> Vec<F> {
+    let a_len = a
+        .iter()
+        .rposition(|x| *x != F::default())
+        .map_or(0, |pos| pos + 1);
+    let a = &a[..a_len];
+    if a.len() < b.len() {
+        return a.to_vec();
+    }
+
+    let k = a.len() - b.len() + 1;
+    let a_reversed: Vec<F> = a.iter().rev().take(k).copied().collect();
+    let b_reversed: Vec<F> = b.iter().rev().copied().collect();
+    let mut quotient = mul(&a_reversed, &inverse_series(&b_reversed, k));
+    quotient.truncate(k);
+    quotient.reverse();
+
+    let product = mul(b, &quotient);
+    a.iter()
+        .zip(product)
+        .take(b.len() - 1)
+        .map(|(a_i, p_i)| *a_i - p_i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polynomial::Polynomial;
+    use crate::polynomial::multipoint::mul;
+
+    type Field = ark_mnt6_753::Fr;
+
+    #[test]
+    fn evaluate_batch_matches_evaluate() {
+        let mut rng = rand::rng();
+        for (degree, num_points) in [(5, 3), (5, 100), (100, 5), (80, 130), (150, 40)] {
+            let poly: Polynomial<Field> = Polynomial::random(degree, &mut rng);
+            let points: Vec<Field> = (0..num_points)
+                .map(|_| crate::helpers::rand_scalar(&mut rng))
+                .collect();
+            let expected: Vec<Field> = points.iter().map(|x| poly.evaluate(x)).collect();
+            assert_eq!(poly.evaluate_batch(&points), expected);
+        }
+
+        let poly: Polynomial<Field> = Polynomial::random(40, &mut rng);
+        let points: Vec<Field> = (0..50u64).map(|i| Field::from(i % 7)).collect();
+        let expected: Vec<Field> = points.iter().map(|x| poly.evaluate(x)).collect();
+        assert_eq!(poly.evaluate_batch(&points), expected);
+        assert!(poly.evaluate_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn fft_mul_matches_schoolbook() {
+        let mut rng = rand::rng();
+        let a: Polynomial<Field> = Polynomial::random(40, &mut rng);
+        let b: Polynomial<Field> = Polynomial::random(70, &mut rng);
+        assert_eq!(
+            Polynomial::new(mul(a.coefficients(), b.coefficients())),
+            &a * &b
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::circuits::QAP;
+use crate::groth16::{CurveId, Proof, TrustedSetupOutput, VerifyingKeyAffine};
+use crate::helpers::{from_bytes, to_bytes};
+use ark_ec::pairing::Pairing;
+use rootcause::Report;
+use wasm_bindgen::prelude::*;
+
+/// A proof along with the verifying key of the trusted setup it was made over, returned by `prove`.
+#[wasm_bindgen]
+pub struct ProofWithKey {
+    proof: Vec<u8>,
+    verifying_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ProofWithKey {
+    /// The proof, as `Proof::to_bytes`.
+    #[wasm_bindgen(getter)]
+    pub fn proof(&self) -> Vec<u8> {
+        self.proof.clone()
+    }
+
+    /// The verifying key, as `VerifyingKeyAffine::to_bytes`.
+    #[wasm_bindgen(getter, js_name = verifyingKey)]
+    pub fn verifying_key(&self) -> Vec<u8> {
+        self.verifying_key.clone()
+    }
+}
+
+/// Run a new trusted setup for an encoded QAP and prove for an encoded witness, over the curve named `curve` as
+/// parsed by `CurveId`. The setup is discarded afterwards, so its verifying key is returned with the proof.
+/// Everything is in the compressed canonical arkworks encoding, with the witness encoded as a `Vec` of scalars.
+#[wasm_bindgen]
+pub fn prove(curve: &str, qap: &[u8], witness: &[u8]) -> Result<ProofWithKey, JsError> {
+    let curve: CurveId = curve.parse().map_err(to_js_error)?;
+    match curve {
+        CurveId::Mnt6_753 => prove_over::<ark_mnt6_753::MNT6_753>(qap, witness),
+        #[cfg(feature = "solidity")]
+        CurveId::Bn254 => prove_over::<ark_bn254::Bn254>(qap, witness),
+    }
+    .map_err(to_js_error)
+}
+
+/// Verify an encoded proof against an encoded verifying key and public witness, as `Proof::verify_with_key`, over
+/// the curve named `curve`. The encodings are as in `prove`.
+#[wasm_bindgen]
+pub fn verify(
+    curve: &str,
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_witness: &[u8],
+) -> Result<bool, JsError> {
+    let curve: CurveId = curve.parse().map_err(to_js_error)?;
+    match curve {
+        CurveId::Mnt6_753 => {
+            verify_over::<ark_mnt6_753::MNT6_753>(verifying_key, proof, public_witness)
+        }
+        #[cfg(feature = "solidity")]
+        CurveId::Bn254 => verify_over::<ark_bn254::Bn254>(verifying_key, proof, public_witness),
+    }
+    .map_err(to_js_error)
+}
+
+fn prove_over<C: Pairing>(qap: &[u8], witness: &[u8]) -> Result<ProofWithKey, Report> {
+    let qap: QAP<C::ScalarField> = from_bytes(qap, "Decoding QAP")?;
+    let witness: Vec<C::ScalarField> = from_bytes(witness, "Decoding witness")?;
+    let setup = TrustedSetupOutput::<C>::new(qap)?;
+    Ok(ProofWithKey {
+        proof: setup.prove(&witness)?.to_bytes(),
+        verifying_key: to_bytes(&setup.verifying_key_affine()),
+    })
+}
+
+fn verify_over<C: Pairing>(
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_witness: &[u8],
+) -> Result<bool, Report> {
+    let verifying_key = VerifyingKeyAffine::<C>::from_bytes(verifying_key)?;
+    let proof = Proof::<C>::from_bytes(proof)?;
+    let public_witness: Vec<C::ScalarField> =
+        from_bytes(public_witness, "Decoding public witness")?;
+    proof.verify_with_key(&verifying_key, &public_witness)
+}
+
+fn to_js_error(report: Report) -> JsError {
+    JsError::new(&format!("{report}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::{QAP, R1CS};
+    use crate::helpers::to_bytes;
+    use crate::wasm::{prove, verify};
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    type Field = ark_mnt6_753::Fr;
+
+    // Runs under `wasm-bindgen-test-runner` on wasm32, and as an ordinary test natively
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn proof_round_trips_through_bytes() {
+        let l = vec![vec![0], vec![1], vec![0]];
+        let r = vec![vec![0], vec![0], vec![1]];
+        let o = vec![vec![1], vec![0], vec![0]];
+        let r1cs: R1CS<Field> = R1CS::new(l, r, o, vec![6]);
+        let qap = to_bytes(&QAP::from(r1cs));
+        let witness = to_bytes(&[6, 2, 3].map(Field::from).to_vec());
+
+        let output = prove("MNT6-753", &qap, &witness).unwrap();
+        let (key, proof) = (output.verifying_key(), output.proof());
+        assert!(verify("MNT6-753", &key, &proof, &to_bytes(&vec![Field::from(6)])).unwrap());
+        assert!(!verify("MNT6-753", &key, &proof, &to_bytes(&vec![Field::from(7)])).unwrap());
+    }
+}